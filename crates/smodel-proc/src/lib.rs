@@ -29,7 +29,7 @@ use syn::parse::{Parse, ParseStream, Result};
 use syn::punctuated::Punctuated;
 use syn::token::Comma;
 // use syn::spanned::Spanned;
-use syn::{braced, parenthesized, parse_macro_input, Attribute, Expr, FnArg, Generics, Ident, Pat, Path, Stmt, Token, Type, Visibility, WhereClause};
+use syn::{braced, parenthesized, parse_macro_input, AngleBracketedGenericArguments, Attribute, Expr, FnArg, GenericParam, Generics, Ident, ImplItem, LitStr, Pat, Path, Stmt, Token, Type, Visibility, WhereClause};
 
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -53,29 +53,111 @@ const DATA_VARIANT_PREFIX: &'static str = "__variant_";
 /// Variant name used for indicating that no subtype is instantiated.
 const DATA_VARIANT_NO_SUBTYPE: &'static str = "__Nothing";
 
+/// Variant name used for holding a `PhantomData` value in enumerations
+/// of subtypes of generic data types. The variant is never constructed.
+const DATA_PHANTOM: &'static str = "__Phantom";
+
+/// Field name used for holding a `PhantomData` value in data
+/// structures of generic data types.
+const DATA_PHANTOM_FIELD: &'static str = "__phantom";
+
+/// Field name used for holding the object ID in the basemost data structure.
+const DATA_ID_FIELD: &'static str = "__id";
+
+/// Suffix appended to the basemost data type's name for the enumeration
+/// of the non-abstract data types, identifying the data type an instance
+/// was constructed as.
+const TAG_SUFFIX: &'static str = "Tag";
+
+/// Prefix used for the static items of the static members
+/// of data types, at the `#DATA` module, as in `__static_M_NAME`.
+const DATA_STATIC_PREFIX: &str = "__static_";
+
+/// Suffix appended to the basemost data type's name for the enumeration
+/// holding a handle of the data type an instance was constructed as.
+const KIND_SUFFIX: &str = "Kind";
+
+/// Suffix appended to the basemost data type's name for the
+/// visitor trait over the data types.
+const VISITOR_SUFFIX: &str = "Visitor";
+
+/// Field name used for holding the data type tag in the basemost
+/// data structure, also used as the name of its accessor method.
+const DATA_TAG_FIELD: &'static str = "__tag";
+
+/// Names of the methods contributed to data types, which methods
+/// and field getters of data types must not take.
+const RESERVED_METHOD_NAMES: [&str; 10] = ["id", "is_alive", "kind", "type_name", "static_type_name", "to", "is", "deep_clone_into", "accept", "as_kind"];
+
 struct SmTypeTree {
     smodel_path: proc_macro2::TokenStream,
     arena_type_name: proc_macro2::TokenStream,
     data_types: Vec<Rc<SmType>>,
+    interfaces: Vec<Rc<SmInterface>>,
 }
 
 struct SmType {
     attributes: Vec<Attribute>,
+    options: SmTypeOptions,
     visibility: Visibility,
+    is_abstract: bool,
+    is_final: bool,
     name: Ident,
+    generics: Generics,
     inherits: Option<Ident>,
+    inherits_arguments: Option<AngleBracketedGenericArguments>,
+    interfaces: Vec<Ident>,
     fields: Vec<Rc<SmTypeField>>,
+    consts: Vec<Rc<SmTypeConst>>,
     constructor: Option<SmTypeConstructor>,
     methods: Vec<Rc<SmTypeMethod>>,
+    impls: Vec<Rc<SmTypeImpl>>,
+}
+
+/// Options given through `#[smodel(...)]` attributes of a data type.
+#[derive(Default)]
+struct SmTypeOptions {
+    /// Data layout, either `"nested"` or `"flat"`. Only
+    /// allowed at the basemost data type.
+    layout: Option<LitStr>,
+    /// Whether panics due to a dropped arena name the data type
+    /// and field accessed. Only allowed at the basemost data type.
+    verbose_panics: Option<Span>,
+    /// Whether the `Debug` output includes the fields of the
+    /// data type and its subtypes.
+    debug: bool,
+    /// Whether the arena and data types implement serde's `Serialize`
+    /// and `Deserialize`. Only allowed at the basemost data type.
+    serde: Option<Span>,
+    /// Whether a method hiding a base method without `override`
+    /// or `new` is reported as either an `"error"` or a `"warn"`ing.
+    /// Only allowed at the basemost data type.
+    missing_override: Option<LitStr>,
 }
 
 struct SmTypeField {
+    /// Attributes of the getter, such as RustDoc comments.
+    attributes: Vec<Attribute>,
+    getter_visibility: Visibility,
+    setter_visibility: Visibility,
     is_ref: bool,
     name: Ident,
     type_annotation: Type,
     default_value: Expr,
 }
 
+/// A constant or static member of a data type, as in `const NAME: T = value;`,
+/// which subtypes may override.
+struct SmTypeConst {
+    attributes: Vec<Attribute>,
+    visibility: Visibility,
+    is_override: bool,
+    is_static: bool,
+    name: Ident,
+    type_annotation: Type,
+    value: Expr,
+}
+
 enum SmTypeMethodOrConstructor {
     Method(SmTypeMethod),
     Constructor(SmTypeConstructor),
@@ -94,12 +176,48 @@ struct SmTypeConstructor {
 struct SmTypeMethod {
     attributes: RefCell<Vec<Attribute>>,
     visibility: Visibility,
+    is_abstract: bool,
+    is_final: bool,
     is_override: bool,
+    /// Whether the method intentionally hides a base method, as in `new fn m`.
+    is_new: bool,
     name: Ident,
     generics: Generics,
     inputs: Punctuated<FnArg, Comma>,
     result_type: Option<Type>,
     statements: proc_macro2::TokenStream,
+    /// The path of the trait, if the method belongs to a trait implementation.
+    trait_name: Option<String>,
+}
+
+/// An interface that data types from different branches may implement,
+/// as in `interface I { fn m(&self); }`.
+struct SmInterface {
+    attributes: Vec<Attribute>,
+    visibility: Visibility,
+    name: Ident,
+    methods: Vec<SmInterfaceMethod>,
+}
+
+struct SmInterfaceMethod {
+    attributes: Vec<Attribute>,
+    name: Ident,
+    generics: Generics,
+    inputs: Punctuated<FnArg, Comma>,
+    result_type: Option<Type>,
+}
+
+/// A trait implementation for a data type, as in `impl Trait for M { ... }`.
+struct SmTypeImpl {
+    attributes: Vec<Attribute>,
+    generics: Generics,
+    trait_path: Path,
+    /// The trait path as a string, without a leading `::`,
+    /// identifying the trait.
+    trait_name: String,
+    self_type: Ident,
+    associated_items: Vec<ImplItem>,
+    methods: Vec<Rc<SmTypeMethod>>,
 }
 
 impl Parse for SmTypeTree {
@@ -113,14 +231,51 @@ impl Parse for SmTypeTree {
             input.parse::<Token![;]>()?;
         }
         let arena_type_name = parse_smtype_arena_type_name(input)?.to_token_stream();
-        let mut data_types = vec![];
+        let mut data_types: Vec<SmType> = vec![];
+        let mut impls: Vec<SmTypeImpl> = vec![];
+        let mut interfaces: Vec<Rc<SmInterface>> = vec![];
         while !input.is_empty() {
-            data_types.push(Rc::new(input.parse::<SmType>()?));
+            let fork = input.fork();
+            Attribute::parse_outer(&fork)?;
+            if fork.peek(Token![impl]) {
+                impls.push(input.parse::<SmTypeImpl>()?);
+                continue;
+            }
+            fork.parse::<Visibility>()?;
+            if fork.peek(Ident) {
+                interfaces.push(Rc::new(input.parse::<SmInterface>()?));
+            } else {
+                data_types.push(input.parse::<SmType>()?);
+            }
+        }
+
+        // A data type inheriting no base may list interfaces
+        // in place of the base, as in `struct Thingy: I`.
+        for m in data_types.iter_mut() {
+            if let Some(inherits) = m.inherits.clone() {
+                if interfaces.iter().any(|i| i.name == inherits) {
+                    if let Some(arguments) = &m.inherits_arguments {
+                        return Err(syn::Error::new(arguments.span(), "Interface must not be given generic parameters."));
+                    }
+                    m.interfaces.insert(0, inherits);
+                    m.inherits = None;
+                }
+            }
         }
+
+        // Contribute each trait implementation to its data type.
+        for imp in impls {
+            let Some(m) = data_types.iter_mut().find(|m| m.name == imp.self_type) else {
+                return Err(syn::Error::new(imp.self_type.span(), format!("Data type '{}' not found.", imp.self_type)));
+            };
+            m.impls.push(Rc::new(imp));
+        }
+
         Ok(Self {
             smodel_path: smodel_path.map(|p| p.to_token_stream()).unwrap_or(proc_macro2::TokenStream::from_str("::smodel").unwrap()),
             arena_type_name,
-            data_types,
+            data_types: data_types.into_iter().map(Rc::new).collect(),
+            interfaces,
         })
     }
 }
@@ -131,30 +286,63 @@ fn parse_full_qualified_id(input: ParseStream) -> Result<Path> {
 
 impl Parse for SmType {
     fn parse(input: ParseStream) -> Result<Self> {
-        let attributes = Attribute::parse_outer(input)?;
+        let mut attributes = Attribute::parse_outer(input)?;
+        let options = parse_smtype_options(&mut attributes)?;
         let visibility = input.parse::<Visibility>()?;
+
+        let is_abstract = if input.peek(Token![abstract]) {
+            input.parse::<Token![abstract]>()?;
+            true
+        } else {
+            false
+        };
+        let is_final = if input.peek(Token![final]) {
+            input.parse::<Token![final]>()?;
+            true
+        } else {
+            false
+        };
  
         input.parse::<Token![struct]>()?;
  
         let name = input.parse::<Ident>()?;
+        if is_abstract && is_final {
+            name.span().unwrap().error("Data type must not be both abstract and final.").emit();
+        }
         let name_str = name.to_string();
+        let mut generics = input.parse::<Generics>()?;
 
         // Inherits
         let mut inherits: Option<Ident> = None;
+        let mut inherits_arguments: Option<AngleBracketedGenericArguments> = None;
         if input.peek(Token![:]) {
             input.parse::<Token![:]>()?;
             inherits = Some(input.parse::<Ident>()?);
+            if input.peek(Token![<]) {
+                inherits_arguments = Some(input.parse::<AngleBracketedGenericArguments>()?);
+            }
+        }
+
+        // Interfaces
+        let mut interfaces: Vec<Ident> = vec![];
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            interfaces.push(input.parse::<Ident>()?);
         }
+        generics.where_clause = input.parse::<Option<WhereClause>>()?;
 
         let mut fields: Vec<Rc<SmTypeField>> = vec![];
+        let mut consts: Vec<Rc<SmTypeConst>> = vec![];
         let mut constructor: Option<SmTypeConstructor> = None;
         let mut methods: Vec<Rc<SmTypeMethod>> = vec![];
         let braced_content;
         let _ = braced!(braced_content in input);
 
         while !braced_content.is_empty() {
-            if braced_content.peek(Token![let]) {
+            if peek_smtype_field(&braced_content) {
                 fields.push(Rc::new(parse_smtype_field(&braced_content)?));
+            } else if peek_smtype_const(&braced_content) {
+                consts.push(Rc::new(parse_smtype_const(&braced_content)?));
             } else {
                 match parse_smtype_method(&braced_content, &name_str)? {
                     SmTypeMethodOrConstructor::Constructor(ctor) => {
@@ -169,17 +357,215 @@ impl Parse for SmType {
 
         Ok(Self {
             attributes,
+            options,
             visibility,
+            is_abstract,
+            is_final,
             name,
+            generics,
             inherits,
+            inherits_arguments,
+            interfaces,
             fields,
+            consts,
             constructor,
             methods,
+            impls: vec![],
         })
     }
 }
 
+impl Parse for SmInterface {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attributes = Attribute::parse_outer(input)?;
+        let visibility = input.parse::<Visibility>()?;
+        let interface_id = input.parse::<Ident>()?;
+        if interface_id != "interface" {
+            return Err(syn::Error::new(interface_id.span(), "Expected `struct` or `interface`."));
+        }
+        let name = input.parse::<Ident>()?;
+
+        let mut methods: Vec<SmInterfaceMethod> = vec![];
+        let braced_content;
+        let _ = braced!(braced_content in input);
+
+        while !braced_content.is_empty() {
+            let attributes = Attribute::parse_outer(&braced_content)?;
+            braced_content.parse::<Token![fn]>()?;
+            let name = braced_content.parse::<Ident>()?;
+            let mut generics = braced_content.parse::<Generics>()?;
+            let parens_content;
+            parenthesized!(parens_content in braced_content);
+            let inputs = parens_content.parse_terminated(FnArg::parse, Comma)?;
+            match inputs.first() {
+                Some(FnArg::Receiver(rec)) if rec.colon_token.is_some() => {
+                    return Err(syn::Error::new(rec.span(), "Typed receivers are not supported; use either `&self` or `self`."));
+                },
+                Some(FnArg::Receiver(rec)) if rec.mutability.is_none() || rec.reference.is_none() => {},
+                _ => return Err(syn::Error::new(name.span(), "Interface method must have either a `&self` or `self` receiver.")),
+            }
+            let result_type: Option<Type> = if braced_content.peek(Token![->]) {
+                braced_content.parse::<Token![->]>()?;
+                Some(braced_content.parse::<Type>()?)
+            } else {
+                None
+            };
+            generics.where_clause = braced_content.parse::<Option<WhereClause>>()?;
+            braced_content.parse::<Token![;]>()?;
+            methods.push(SmInterfaceMethod {
+                attributes,
+                name,
+                generics,
+                inputs,
+                result_type,
+            });
+        }
+
+        Ok(Self {
+            attributes,
+            visibility,
+            name,
+            methods,
+        })
+    }
+}
+
+impl Parse for SmTypeImpl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attributes = Attribute::parse_outer(input)?;
+        input.parse::<Token![impl]>()?;
+        let mut generics = input.parse::<Generics>()?;
+        let trait_path = input.parse::<Path>()?;
+        let trait_name = tokens_to_string(&trait_path.segments);
+        input.parse::<Token![for]>()?;
+        let self_type = input.parse::<Ident>()?;
+        if input.peek(Token![<]) {
+            // The generic parameters of the model are implied.
+            input.parse::<AngleBracketedGenericArguments>()?;
+        }
+        generics.where_clause = input.parse::<Option<WhereClause>>()?;
+
+        let mut associated_items: Vec<ImplItem> = vec![];
+        let mut methods: Vec<Rc<SmTypeMethod>> = vec![];
+        let braced_content;
+        let _ = braced!(braced_content in input);
+
+        while !braced_content.is_empty() {
+            let fork = braced_content.fork();
+            Attribute::parse_outer(&fork)?;
+            if fork.peek(Token![type]) || fork.peek(Token![const]) {
+                associated_items.push(braced_content.parse::<ImplItem>()?);
+                continue;
+            }
+            let SmTypeMethodOrConstructor::Method(mut m) = parse_smtype_method(&braced_content, "")? else {
+                unreachable!();
+            };
+            m.trait_name = Some(trait_name.clone());
+            methods.push(Rc::new(m));
+        }
+
+        Ok(Self {
+            attributes,
+            generics,
+            trait_path,
+            trait_name,
+            self_type,
+            associated_items,
+            methods,
+        })
+    }
+}
+
+impl SmTypeImpl {
+    /// Whether the implemented trait is `Debug`.
+    fn is_debug_trait(&self) -> bool {
+        ["Debug", "fmt::Debug", "std::fmt::Debug", "core::fmt::Debug"].contains(&self.trait_name.as_str())
+    }
+}
+
+/// Removes `#[smodel(...)]` attributes from a data type's attributes,
+/// parsing them into options.
+fn parse_smtype_options(attributes: &mut Vec<Attribute>) -> Result<SmTypeOptions> {
+    let mut options = SmTypeOptions::default();
+    for attr in attributes.iter() {
+        if !attr.path().is_ident("smodel") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("layout") {
+                let layout = meta.value()?.parse::<LitStr>()?;
+                if !["nested", "flat"].contains(&layout.value().as_str()) {
+                    return Err(syn::Error::new(layout.span(), "Layout must be either \"nested\" or \"flat\"."));
+                }
+                options.layout = Some(layout);
+                Ok(())
+            } else if meta.path.is_ident("verbose_panics") {
+                options.verbose_panics = Some(meta.path.span());
+                Ok(())
+            } else if meta.path.is_ident("debug") {
+                options.debug = true;
+                Ok(())
+            } else if meta.path.is_ident("serde") {
+                if cfg!(not(feature = "serde")) {
+                    return Err(meta.error("The `serde` option requires the `serde` feature of the `smodel` crate."));
+                }
+                options.serde = Some(meta.path.span());
+                Ok(())
+            } else if meta.path.is_ident("missing_override") {
+                let level = meta.value()?.parse::<LitStr>()?;
+                if !["error", "warn"].contains(&level.value().as_str()) {
+                    return Err(syn::Error::new(level.span(), "Level must be either \"error\" or \"warn\"."));
+                }
+                options.missing_override = Some(level);
+                Ok(())
+            } else {
+                Err(meta.error("Unrecognized smodel option."))
+            }
+        })?;
+    }
+    attributes.retain(|attr| !attr.path().is_ident("smodel"));
+    Ok(options)
+}
+
+/// Determines whether a field follows.
+fn peek_smtype_field(input: ParseStream) -> bool {
+    let fork = input.fork();
+    if Attribute::parse_outer(&fork).is_err() {
+        return false;
+    }
+    if fork.parse::<Token![pub]>().is_ok() && fork.peek(syn::token::Paren) {
+        let _ = fork.parse::<proc_macro2::TokenTree>();
+    }
+    fork.peek(Token![let])
+}
+
+/// Parses the visibility of a field as the visibility of its getter
+/// and setter. `pub(get)` gives a public getter and a private setter.
+fn parse_smtype_field_visibility(input: ParseStream) -> Result<(Visibility, Visibility)> {
+    if !input.peek(Token![pub]) {
+        return Ok((Visibility::Inherited, Visibility::Inherited));
+    }
+    let pub_token = input.parse::<Token![pub]>()?;
+    if !input.peek(syn::token::Paren) {
+        return Ok((Visibility::Public(pub_token), Visibility::Public(pub_token)));
+    }
+    let parens_content;
+    parenthesized!(parens_content in input);
+    if parens_content.peek(Ident) {
+        let id = parens_content.parse::<Ident>()?;
+        if id != "get" || !parens_content.is_empty() {
+            return Err(syn::Error::new(id.span(), "Expected `get`, `crate`, `self`, `super` or `in`."));
+        }
+        return Ok((Visibility::Public(pub_token), Visibility::Inherited));
+    }
+    let restriction = parens_content.parse::<proc_macro2::TokenStream>()?;
+    let visibility = syn::parse2::<Visibility>(quote! { #pub_token(#restriction) })?;
+    Ok((visibility.clone(), visibility))
+}
+
 fn parse_smtype_field(input: ParseStream) -> Result<SmTypeField> {
+    let attributes = Attribute::parse_outer(input)?;
+    let (getter_visibility, setter_visibility) = parse_smtype_field_visibility(input)?;
     input.parse::<Token![let]>()?;
     let is_ref = if input.peek(Token![ref]) {
         input.parse::<Token![ref]>()?;
@@ -195,6 +581,9 @@ fn parse_smtype_field(input: ParseStream) -> Result<SmTypeField> {
     input.parse::<Token![;]>()?;
 
     Ok(SmTypeField {
+        attributes,
+        getter_visibility,
+        setter_visibility,
         is_ref,
         name,
         type_annotation,
@@ -202,21 +591,97 @@ fn parse_smtype_field(input: ParseStream) -> Result<SmTypeField> {
     })
 }
 
+/// Determines whether a constant or static member follows.
+fn peek_smtype_const(input: ParseStream) -> bool {
+    let fork = input.fork();
+    if Attribute::parse_outer(&fork).is_err() || fork.parse::<Visibility>().is_err() {
+        return false;
+    }
+    if fork.peek(Token![override]) {
+        let _ = fork.parse::<Token![override]>();
+    }
+    (fork.peek(Token![const]) && !fork.peek2(Token![fn])) || fork.peek(Token![static])
+}
+
+fn parse_smtype_const(input: ParseStream) -> Result<SmTypeConst> {
+    let attributes = Attribute::parse_outer(input)?;
+    let visibility = input.parse::<Visibility>()?;
+    let is_override = if input.peek(Token![override]) {
+        input.parse::<Token![override]>()?;
+        true
+    } else {
+        false
+    };
+    let is_static = if input.peek(Token![static]) {
+        input.parse::<Token![static]>()?;
+        true
+    } else {
+        input.parse::<Token![const]>()?;
+        false
+    };
+    let name = input.parse::<Ident>()?;
+    input.parse::<Token![:]>()?;
+    let type_annotation = input.parse::<Type>()?;
+    input.parse::<Token![=]>()?;
+    let value = input.parse::<Expr>()?;
+    input.parse::<Token![;]>()?;
+
+    Ok(SmTypeConst {
+        attributes,
+        visibility,
+        is_override,
+        is_static,
+        name,
+        type_annotation,
+        value,
+    })
+}
+
 fn parse_smtype_method(input: ParseStream, smtype_name: &str) -> Result<SmTypeMethodOrConstructor> {
     let attributes = Attribute::parse_outer(input)?;
     let visibility = input.parse::<Visibility>()?;
+    let is_abstract = if input.peek(Token![abstract]) {
+        input.parse::<Token![abstract]>()?;
+        true
+    } else {
+        false
+    };
+    let is_final = if input.peek(Token![final]) {
+        input.parse::<Token![final]>()?;
+        true
+    } else {
+        false
+    };
     let is_override = if input.peek(Token![override]) {
         input.parse::<Token![override]>()?;
         true
     } else {
         false
     };
+    let is_new = if input.peek(Ident) && input.peek2(Token![fn]) {
+        let new_id = input.parse::<Ident>()?;
+        if new_id != "new" {
+            return Err(syn::Error::new(new_id.span(), "Expected `new` or `fn`."));
+        }
+        if is_override {
+            new_id.span().unwrap().error("Method must not be both `new` and `override`.").emit();
+        }
+        true
+    } else {
+        false
+    };
     input.parse::<Token![fn]>()?;
     let mut is_constructor = false;
     let id = input.parse::<Ident>()?;
-    if !is_override && id.to_string() == smtype_name {
+    if !is_override && !is_new && id.to_string() == smtype_name {
         // id.span().unwrap().error("Identifier must be equals \"constructor\"").emit();
         is_constructor = true;
+        if is_abstract {
+            id.span().unwrap().error("Constructor must not be abstract.").emit();
+        }
+        if is_final {
+            id.span().unwrap().error("Constructor must not be final.").emit();
+        }
     }
     let mut generics = input.parse::<Generics>()?;
 
@@ -233,6 +698,25 @@ fn parse_smtype_method(input: ParseStream, smtype_name: &str) -> Result<SmTypeMe
 
     generics.where_clause = if input.peek(Token![where]) { Some(input.parse::<WhereClause>()?) } else { None };
 
+    // An abstract method has no body.
+    if is_abstract && !is_constructor {
+        input.parse::<Token![;]>()?;
+        return Ok(SmTypeMethodOrConstructor::Method(SmTypeMethod {
+            attributes: RefCell::new(attributes),
+            visibility,
+            is_abstract,
+            is_final,
+            is_override,
+            is_new,
+            name: id,
+            generics,
+            inputs,
+            result_type,
+            statements: proc_macro2::TokenStream::new(),
+            trait_name: None,
+        }));
+    }
+
     let braced_content;
     let _ = braced!(braced_content in input);
 
@@ -241,12 +725,16 @@ fn parse_smtype_method(input: ParseStream, smtype_name: &str) -> Result<SmTypeMe
         return Ok(SmTypeMethodOrConstructor::Method(SmTypeMethod {
             attributes: RefCell::new(attributes),
             visibility,
+            is_abstract,
+            is_final,
             is_override,
+            is_new,
             name: id,
             generics,
             inputs,
             result_type,
             statements,
+            trait_name: None,
         }));
     }
 
@@ -288,7 +776,7 @@ fn parse_smtype_arena_type_name(input: ParseStream) -> Result<Path> {
 #[proc_macro]
 pub fn smodel(input: TokenStream) -> TokenStream {
     let SmTypeTree {
-        smodel_path, arena_type_name, data_types
+        smodel_path, arena_type_name, data_types, interfaces
     } = parse_macro_input!(input as SmTypeTree);
 
     let mut host = SModelHost::new();
@@ -301,30 +789,75 @@ pub fn smodel(input: TokenStream) -> TokenStream {
         panic!("There must be at least one data type.");
     }
 
-    // 2. Ensure the first type inherits no other one.
+    // 2. Order data types so that every data type comes after its
+    // inherited base, regardless of definition order.
+    // This also reports inheritance cycles.
+
+    let Some(data_types) = DataTypeOrdering().exec(&data_types) else {
+        return TokenStream::new();
+    };
+
+    // 3. Ensure exactly one data type inherits no other one.
 
-    if data_types[0].inherits.is_some() {
-        data_types[0].name.span().unwrap().error("First data type must inherit no base.").emit();
+    let mut data_types_without_base = data_types.iter().filter(|m| m.inherits.is_none());
+    if data_types_without_base.next().is_none() {
+        data_types[0].name.span().unwrap().error("There must be a data type that inherits no base.").emit();
+        return TokenStream::new();
+    }
+    if let Some(m) = data_types_without_base.next() {
+        m.name.span().unwrap().error("Data type must inherit a base.").emit();
         return TokenStream::new();
     }
     let base_smtype_data_name = Ident::new(&(DATA_PREFIX.to_string() + &data_types[0].name.to_string()), Span::call_site());
 
-    // 3. Ensure all other types inherit another one.
+    // 4. Ensure the layout and the `verbose_panics`, `serde` and
+    // `missing_override` options are only specified at the basemost data type.
 
     for m in data_types[1..].iter() {
-        if m.inherits.is_none() {
-            m.name.span().unwrap().error("Data type must inherit a base.").emit();
+        if let Some(layout) = &m.options.layout {
+            layout.span().unwrap().error("Layout must be specified at the basemost data type.").emit();
+            return TokenStream::new();
+        }
+        if let Some(level) = &m.options.missing_override {
+            level.span().unwrap().error("Option must be specified at the basemost data type.").emit();
+            return TokenStream::new();
+        }
+        if let Some(span) = m.options.verbose_panics.as_ref().or(m.options.serde.as_ref()) {
+            span.unwrap().error("Option must be specified at the basemost data type.").emit();
             return TokenStream::new();
         }
     }
+    host.flat_layout = data_types[0].options.layout.as_ref().map(|l| l.value() == "flat").unwrap_or(false);
+    host.verbose_panics = data_types[0].options.verbose_panics.is_some();
+    host.serde = data_types[0].options.serde.is_some();
+    host.warn_missing_override = data_types[0].options.missing_override.as_ref().map(|l| l.value() == "warn").unwrap_or(false);
+
+    // 5. Ensure every data type declares the generic parameters of
+    // the basemost data type and supplies them to its base.
+
+    if !validate_generics(&data_types) {
+        return TokenStream::new();
+    }
+    host.generics = data_types[0].generics.clone();
+
+    // 6. Ensure interface names are unique.
+
+    for (i, interface) in interfaces.iter().enumerate() {
+        if data_types.iter().any(|m| m.name == interface.name) || interfaces[..i].iter().any(|i| i.name == interface.name) {
+            interface.name.span().unwrap().error(format!("Redefining '{}'", interface.name)).emit();
+            return TokenStream::new();
+        }
+    }
+    host.interfaces = interfaces;
 
     // # Processing steps
 
     let data_id = Ident::new(DATA, Span::call_site());
 
     // 1. Output the arena type.
+    let (impl_generics, ty_generics, where_clause) = host.split_generics();
     host.output.extend::<TokenStream>(quote! {
-        pub type #arena_type_name = #smodel_path::Arena<#data_id::#base_smtype_data_name>;
+        pub type #arena_type_name #ty_generics = #smodel_path::Arena<#data_id::#base_smtype_data_name #ty_generics>;
     }.try_into().unwrap());
 
     // 2. Traverse each type in a first pass.
@@ -334,6 +867,34 @@ pub fn smodel(input: TokenStream) -> TokenStream {
         }
     }
 
+    // 2.1. Contribute the `#FirstM#TAG_SUFFIX` enumeration of non-abstract
+    // data types at the `#DATA` module, re-exported by the enclosing module.
+    let tag_enum = tag_enum_name(&host.semantics.get(&data_types[0]).unwrap());
+    let tag_enum_vis = data_types[0].visibility.clone();
+    let tags = data_types.iter().filter(|m| !m.is_abstract).map(|m| m.name.clone()).collect::<Vec<_>>();
+    let tag_names = tags.iter().map(|t| t.to_string());
+    let tag_enum_doc = format!("Identifies the data type a `{}` was constructed as.", data_types[0].name);
+    host.output.extend::<TokenStream>(quote! {
+        #tag_enum_vis use #data_id::#tag_enum;
+    }.try_into().unwrap());
+    host.data_output.extend(quote! {
+        #[doc = #tag_enum_doc]
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+        #[allow(dead_code)]
+        pub enum #tag_enum {
+            #(#tags),*
+        }
+
+        impl #tag_enum {
+            /// Returns the name of the data type.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    #(Self::#tags => #tag_names),*
+                }
+            }
+        }
+    });
+
     // 3. Traverse each type in a second pass.
     for smtype_node in data_types.iter() {
         let Some(smtype) = host.semantics.get(smtype_node) else {
@@ -361,7 +922,7 @@ pub fn smodel(input: TokenStream) -> TokenStream {
 
         // 3.2. Traverse each field.
         for field in smtype_node.fields.iter() {
-            if !ProcessingStep3_2().exec(&mut host, &smtype, field, &base_accessor, &asc_smtype_list, &mut field_output) {
+            if !ProcessingStep3_2().exec(&mut host, &smtype, field, &base_accessor, &asc_smtype_list, &mut field_output, &smodel_path) {
                 return TokenStream::new();
             }
         }
@@ -371,19 +932,50 @@ pub fn smodel(input: TokenStream) -> TokenStream {
         let subtype_enum = Ident::new(&(DATA_VARIANT_PREFIX.to_owned() + &smtype_name), Span::call_site());
         let data_variant_field_id = Ident::new(DATA_VARIANT_FIELD, Span::call_site());
         field_output.extend(quote! {
-            pub #data_variant_field_id: #subtype_enum,
+            pub #data_variant_field_id: #subtype_enum #ty_generics,
         });
 
+        // 3.3.1. Contribute to the basemost #DATA::M:
+        //
+        // * A #DATA_TAG_FIELD field holding the tag of the data type
+        //   an instance was constructed as.
+        // * A #DATA_ID_FIELD field holding the object ID assigned by the arena.
+        if smtype.inherits().is_none() {
+            let data_tag_field = Ident::new(DATA_TAG_FIELD, Span::call_site());
+            let data_id_field = Ident::new(DATA_ID_FIELD, Span::call_site());
+            field_output.extend(quote! {
+                pub #data_tag_field: #tag_enum,
+                pub #data_id_field: usize,
+            });
+        }
+
         // 3.4. Contribute an enumeration of subtypes at the `#DATA` module.
+        //
+        // In the flat layout, the subtype data structures are stored
+        // inline rather than in separate `Rc` allocations.
+        //
+        // If there are generic parameters, a never constructed variant
+        // holds a `PhantomData` value.
         let mut variants: Vec<proc_macro2::TokenStream> = vec![];
         for subtype in smtype.subtypes().iter() {
-            let sn = DATA_PREFIX.to_owned() + &subtype.name();
-            variants.push(proc_macro2::TokenStream::from_str(&format!("{sn}(::std::rc::Rc<{sn}>)")).unwrap());
+            let sn = Ident::new(&(DATA_PREFIX.to_owned() + &subtype.name()), Span::call_site());
+            if host.flat_layout {
+                variants.push(quote! { #sn(#sn #ty_generics) });
+            } else {
+                variants.push(quote! { #sn(::std::rc::Rc<#sn #ty_generics>) });
+            }
         }
         let data_variant_no_subtype = Ident::new(DATA_VARIANT_NO_SUBTYPE, Span::call_site());
         variants.push(data_variant_no_subtype.to_token_stream());
+        if let Some(phantom_type) = host.phantom_type() {
+            let data_phantom = Ident::new(DATA_PHANTOM, Span::call_site());
+            variants.push(quote! {
+                #[allow(dead_code)]
+                #data_phantom(::std::convert::Infallible, #phantom_type)
+            });
+        }
         host.data_output.extend(quote! {
-            pub enum #subtype_enum {
+            pub enum #subtype_enum #impl_generics #where_clause {
                 #(#variants),*
             }
         });
@@ -391,9 +983,16 @@ pub fn smodel(input: TokenStream) -> TokenStream {
         let smtype_data_id = Ident::new(&format!("{DATA_PREFIX}{}", smtype_name), Span::call_site());
 
         // 3.5. Define the data structure #DATA::M at the #DATA module output,
-        // containing all field output.
+        // containing all field output and, if there are generic parameters,
+        // a `PhantomData` field.
+        if let Some(phantom_type) = host.phantom_type() {
+            let data_phantom_field = Ident::new(DATA_PHANTOM_FIELD, Span::call_site());
+            field_output.extend(quote! {
+                pub #data_phantom_field: #phantom_type,
+            });
+        }
         host.data_output.extend(quote! {
-            pub struct #smtype_data_id {
+            pub struct #smtype_data_id #impl_generics #where_clause {
                 #field_output
             }
         });
@@ -410,6 +1009,42 @@ pub fn smodel(input: TokenStream) -> TokenStream {
                 return TokenStream::new();
             }
         }
+
+        // 3.8.1. Traverse each method of trait implementations
+        for imp in smtype_node.impls.iter() {
+            for method in imp.methods.iter() {
+                if !ProcessingStep3_8().exec(&mut host, method, &smtype) {
+                    return TokenStream::new();
+                }
+            }
+        }
+
+        // 3.9. Ensure a non-abstract data type overrides every abstract method.
+        if !ProcessingStep3_9().exec(&mut host, smtype_node, &smtype) {
+            return TokenStream::new();
+        }
+    }
+
+    // 3.10. Define each interface.
+    let basemost = host.semantics.get(&data_types[0]).unwrap();
+    for interface in host.interfaces.clone().iter() {
+        ProcessingStep3_10().exec(&mut host, interface, &basemost, &smodel_path);
+    }
+    if !host.interfaces.is_empty() {
+        basemost.method_output().borrow_mut().extend(quote! {
+            /// Returns whether the object implements the interface `I`.
+            pub fn is_interface<__I: #smodel_path::Interface<Self>>(&self) -> bool {
+                __I::from_object(self.clone()).is_some()
+            }
+        });
+    }
+
+    // 3.11. Contribute the visitor trait and the `accept` method.
+    ProcessingStep3_11().exec(&mut host, &data_types, &basemost);
+
+    // 3.12. Define the constant and static members of each data type.
+    if !ProcessingStep3_12().exec(&mut host, &data_types) {
+        return TokenStream::new();
     }
 
     // 4. Traverse each type in a third pass.
@@ -426,14 +1061,35 @@ pub fn smodel(input: TokenStream) -> TokenStream {
             ProcessingStep4_1().exec(&mut host, method, &smtype);
         }
 
+        // 4.2. Implement a field-aware Debug if requested by the data type
+        // or one of its base data types, unless the data type has
+        // an `impl Debug` block.
+        let implements_debug = smtype_node.impls.iter().any(|imp| imp.is_debug_trait());
+        if !implements_debug && smtype.asc_smtype_list().iter().any(|m| m.is_debug()) {
+            ProcessingStep4_2().exec(&mut host, &smtype, &smodel_path);
+        }
+
+        // 4.3. Implement serialization if requested by the basemost data type.
+        if host.serde {
+            ProcessingStep4_3().exec(&mut host, &smtype, &smodel_path);
+        }
+
+        // 4.4. Implement deep cloning.
+        ProcessingStep4_4().exec(&mut host, &smtype, &arena_type_name.to_string(), &smodel_path);
+
+        // 4.5. Implement the traits of trait implementations.
+        for imp in smtype_node.impls.iter() {
+            ProcessingStep4_5().exec(&mut host, imp, &smtype, &data_types);
+        }
+
         // * Contribute a `to::<T: TryFrom<M>>()` method.
         // * Contribute an `is::<T>()` method.
         smtype.method_output().borrow_mut().extend(quote! {
-            pub fn to<T: TryFrom<#smtype_name_id, Error = #smodel_path::SModelError>>(&self) -> Result<T, #smodel_path::SModelError> {
-                T::try_from(self.clone())
+            pub fn to<__T: TryFrom<Self, Error = #smodel_path::SModelError>>(&self) -> Result<__T, #smodel_path::SModelError> {
+                __T::try_from(self.clone())
             }
-            pub fn is<T: TryFrom<#smtype_name_id, Error = #smodel_path::SModelError>>(&self) -> bool {
-                T::try_from(self.clone()).is_ok()
+            pub fn is<__T: TryFrom<Self, Error = #smodel_path::SModelError>>(&self) -> bool {
+                __T::try_from(self.clone()).is_ok()
             }
         });
 
@@ -441,7 +1097,7 @@ pub fn smodel(input: TokenStream) -> TokenStream {
 
         // Output the code of all methods to an `impl` block for the data type.
         host.output.extend::<TokenStream>(quote! {
-            impl #smtype_name_id {
+            impl #impl_generics #smtype_name_id #ty_generics #where_clause {
                 #method_output
             }
         }.try_into().unwrap());
@@ -463,22 +1119,138 @@ pub fn smodel(input: TokenStream) -> TokenStream {
     host.output
 }
 
-fn convert_function_input_to_arguments(input: &Punctuated<FnArg, Comma>) -> Punctuated<proc_macro2::TokenStream, Comma> {
-    let mut out = Punctuated::<proc_macro2::TokenStream, Comma>::new();
-    for arg in input.iter() {
-        if let FnArg::Receiver(_) = arg {
-            arg.span().unwrap().error("Unexpected receiver.").emit();
-            continue;
-        } else {
-            let FnArg::Typed(pt) = arg else {
-                panic!();
-            };
-            let Pat::Ident(id) = pt.pat.as_ref() else {
-                pt.pat.span().unwrap().error("Pattern must be an identifier.").emit();
-                continue;
+/// Validates the generic parameters of data types. The generic parameters
+/// are declared with their bounds at the basemost data type and shared
+/// by all data types; a subtype declares the same parameters, without
+/// bounds, and supplies them in the same order to its base, as in
+/// `struct Foo<T>: Thingy<T>`.
+fn validate_generics(data_types: &[Rc<SmType>]) -> bool {
+    let basemost = &data_types[0];
+    let mut param_names: Vec<String> = vec![];
+    for param in basemost.generics.params.iter() {
+        match param {
+            GenericParam::Type(p) => param_names.push(p.ident.to_string()),
+            GenericParam::Lifetime(p) => param_names.push(p.lifetime.to_token_stream().to_string()),
+            GenericParam::Const(p) => {
+                p.ident.span().unwrap().error("Const parameters are not supported.").emit();
+                return false;
+            },
+        }
+    }
+    if !param_names.is_empty() {
+        if let Some(span) = &basemost.options.serde {
+            span.unwrap().error("Option `serde` is not supported for generic data types.").emit();
+            return false;
+        }
+    }
+    let expected = format!("<{}>", param_names.join(", "));
+
+    for m in data_types[1..].iter() {
+        // Parameters declared by the subtype
+        let mut names: Vec<String> = vec![];
+        for param in m.generics.params.iter() {
+            let has_bounds = match param {
+                GenericParam::Type(p) => {
+                    names.push(p.ident.to_string());
+                    !p.bounds.is_empty() || p.default.is_some()
+                },
+                GenericParam::Lifetime(p) => {
+                    names.push(p.lifetime.to_token_stream().to_string());
+                    !p.bounds.is_empty()
+                },
+                GenericParam::Const(p) => {
+                    p.ident.span().unwrap().error("Const parameters are not supported.").emit();
+                    return false;
+                },
             };
-            out.push(id.to_token_stream());
+            if has_bounds {
+                param.span().unwrap().error("Bounds must be specified at the basemost data type.").emit();
+                return false;
+            }
+        }
+        if let Some(where_clause) = &m.generics.where_clause {
+            where_clause.span().unwrap().error("Bounds must be specified at the basemost data type.").emit();
+            return false;
+        }
+        if names != param_names {
+            m.name.span().unwrap().error(format!("Data type '{}' must declare the generic parameters {expected} of the basemost data type.", m.name)).emit();
+            return false;
+        }
+
+        // Arguments supplied to the base
+        let arguments: Vec<String> = m.inherits_arguments.iter()
+            .flat_map(|a| a.args.iter().map(|arg| arg.to_token_stream().to_string()))
+            .collect();
+        if arguments != param_names {
+            let inherits = m.inherits.as_ref().unwrap();
+            let span = m.inherits_arguments.as_ref().map(|a| a.span()).unwrap_or(inherits.span());
+            span.unwrap().error(format!("Base '{inherits}' must be given the generic parameters {expected}.")).emit();
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns the name of the enumeration of non-abstract data types
+/// for the model a data type belongs to.
+fn tag_enum_name(smtype: &Symbol) -> Ident {
+    Ident::new(&format!("{}{TAG_SUFFIX}", smtype.asc_smtype_list()[0].name()), Span::call_site())
+}
+
+/// Converts a `CamelCase` name into `snake_case`.
+fn to_snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() {
+            if i != 0 {
+                out.push('_');
+            }
+            out.extend(ch.to_lowercase());
+        } else {
+            out.push(ch);
         }
     }
     out
 }
+
+/// Converts tokens into a string, keeping whitespace only
+/// between identifiers and literals.
+fn tokens_to_string(tokens: &impl ToTokens) -> String {
+    let s = tokens.to_token_stream().to_string();
+    let chars = s.chars().collect::<Vec<_>>();
+    let is_word = |ch: char| ch.is_alphanumeric() || ch == '_' || ch == '\'' || ch == '"';
+    let mut out = String::new();
+    for (i, ch) in chars.iter().enumerate() {
+        if *ch == ' ' && !(i > 0 && is_word(chars[i - 1]) && chars.get(i + 1).map(|ch| is_word(*ch)).unwrap_or(false)) {
+            continue;
+        }
+        out.push(*ch);
+    }
+    out
+}
+
+/// Binds the parameters of a function forwarding its arguments to another
+/// function positionally. A parameter whose pattern is not an identifier,
+/// such as a tuple or `_`, is named `__argN` after its position.
+///
+/// Returns the parameters of the forwarding function along with
+/// the forwarded arguments.
+fn bind_function_inputs(input: &Punctuated<FnArg, Comma>) -> (Punctuated<FnArg, Comma>, Punctuated<proc_macro2::TokenStream, Comma>) {
+    let mut params = Punctuated::<FnArg, Comma>::new();
+    let mut args = Punctuated::<proc_macro2::TokenStream, Comma>::new();
+    for (i, arg) in input.iter().enumerate() {
+        let FnArg::Typed(pt) = arg else {
+            arg.span().unwrap().error("Unexpected receiver.").emit();
+            continue;
+        };
+        let id = match pt.pat.as_ref() {
+            Pat::Ident(id) if id.by_ref.is_none() && id.subpat.is_none() => id.ident.clone(),
+            _ => Ident::new(&format!("__arg{i}"), Span::call_site()),
+        };
+        let mut pt = pt.clone();
+        *pt.pat = syn::parse_quote! { #id };
+        params.push(FnArg::Typed(pt));
+        args.push(id.to_token_stream());
+    }
+    (params, args)
+}
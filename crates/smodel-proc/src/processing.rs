@@ -1,6 +1,9 @@
 mod smodel_host;
 pub use smodel_host::*;
 
+mod data_type_ordering;
+pub use data_type_ordering::*;
+
 mod processing_step_2;
 pub use processing_step_2::*;
 
@@ -16,5 +19,29 @@ pub use processing_step_3_7::*;
 mod processing_step_3_8;
 pub use processing_step_3_8::*;
 
+mod processing_step_3_9;
+pub use processing_step_3_9::*;
+
+mod processing_step_3_10;
+pub use processing_step_3_10::*;
+
+mod processing_step_3_11;
+pub use processing_step_3_11::*;
+
+mod processing_step_3_12;
+pub use processing_step_3_12::*;
+
 mod processing_step_4_1;
-pub use processing_step_4_1::*;
\ No newline at end of file
+pub use processing_step_4_1::*;
+
+mod processing_step_4_2;
+pub use processing_step_4_2::*;
+
+mod processing_step_4_3;
+pub use processing_step_4_3::*;
+
+mod processing_step_4_4;
+pub use processing_step_4_4::*;
+
+mod processing_step_4_5;
+pub use processing_step_4_5::*;
\ No newline at end of file
@@ -0,0 +1,73 @@
+use crate::*;
+
+pub struct DataTypeOrdering();
+
+impl DataTypeOrdering {
+    /// Returns the data types sorted such that every data type comes after
+    /// its inherited base, preserving definition order otherwise.
+    /// Reports an error and returns `None` if a data type is redefined,
+    /// if an inherited base is not found, or if there is an inheritance cycle.
+    pub fn exec(&self, data_types: &[Rc<SmType>]) -> Option<Vec<Rc<SmType>>> {
+        // 1. Map each data type name to its node.
+        let mut data_type_map = HashMap::<String, Rc<SmType>>::new();
+        for m in data_types.iter() {
+            let name = m.name.to_string();
+            if data_type_map.contains_key(&name) {
+                m.name.span().unwrap().error(format!("Redefining '{}'", name)).emit();
+                return None;
+            }
+            data_type_map.insert(name, m.clone());
+        }
+
+        // 2. Visit each data type in definition order, contributing
+        // its inherited bases before itself.
+        let mut output = vec![];
+        let mut visited = HashMap::<String, bool>::new();
+        let mut path = vec![];
+        for m in data_types.iter() {
+            if !self.visit(m, &data_type_map, &mut visited, &mut path, &mut output) {
+                return None;
+            }
+        }
+
+        Some(output)
+    }
+
+    /// Visits a data type. `visited` maps a data type name to `false`
+    /// while its inherited bases are being visited and to `true` once
+    /// it has been contributed to the output.
+    fn visit(&self, m: &Rc<SmType>, data_type_map: &HashMap<String, Rc<SmType>>, visited: &mut HashMap<String, bool>, path: &mut Vec<String>, output: &mut Vec<Rc<SmType>>) -> bool {
+        let name = m.name.to_string();
+        match visited.get(&name) {
+            Some(true) => {
+                return true;
+            },
+            Some(false) => {
+                let i = path.iter().position(|m1| m1 == &name).unwrap();
+                let mut cycle = path[i..].to_vec();
+                cycle.push(name.clone());
+                m.name.span().unwrap().error(format!("Inheritance cycle: {}", cycle.join(" -> "))).emit();
+                return false;
+            },
+            None => {},
+        }
+
+        visited.insert(name.clone(), false);
+        path.push(name.clone());
+
+        if let Some(inherits) = &m.inherits {
+            let Some(inherited) = data_type_map.get(&inherits.to_string()) else {
+                inherits.span().unwrap().error(format!("Data type '{}' not found.", inherits.to_string())).emit();
+                return false;
+            };
+            if !self.visit(inherited, data_type_map, visited, path, output) {
+                return false;
+            }
+        }
+
+        path.pop();
+        visited.insert(name, true);
+        output.push(m.clone());
+        true
+    }
+}
@@ -5,7 +5,7 @@ pub struct ProcessingStep2();
 impl ProcessingStep2 {
     pub fn exec(&self, host: &mut SModelHost, m: &Rc<SmType>) -> bool {
         // 1. Create a SmTypeSlot.
-        let slot = host.factory.create_smtype_slot(m.name.to_string());
+        let slot = host.factory.create_smtype_slot(m.name.to_string(), m.is_abstract, m.is_final, m.options.debug);
 
         // 1.2. Resolve the inherited base.
         // 1.3. If the inherited base failed to resolve, ignore that type
@@ -13,6 +13,10 @@ impl ProcessingStep2 {
         // 1.3.1. Contribute the type to the inherited base's list of subtypes.
         if let Some(inherits) = &m.inherits {
             if let Some(inherited_smtype) = host.smtype_slots.get(&inherits.to_string()) {
+                if inherited_smtype.is_final() {
+                    inherits.span().unwrap().error(format!("Data type '{}' is final.", inherits.to_string())).emit();
+                    return false;
+                }
                 slot.set_inherits(Some(inherited_smtype));
                 inherited_smtype.subtypes().push(slot.clone());
             } else {
@@ -21,6 +25,19 @@ impl ProcessingStep2 {
             }
         }
 
+        // 1.3.2. Resolve the listed interfaces.
+        for interface in m.interfaces.iter() {
+            if !host.interfaces.iter().any(|i| &i.name == interface) {
+                interface.span().unwrap().error(format!("Interface '{}' not found.", interface)).emit();
+                return false;
+            }
+            if slot.interfaces().includes(&interface.to_string()) {
+                interface.span().unwrap().error(format!("Interface '{}' is listed more than once.", interface)).emit();
+                return false;
+            }
+            slot.interfaces().push(interface.to_string());
+        }
+
         // 1.4. Contribute type slot to the set of known type slots.
         if host.smtype_slots.contains_key(&slot.name()) {
             m.name.span().unwrap().error(format!("Redefining '{}'", slot.name())).emit();
@@ -0,0 +1,173 @@
+use crate::*;
+
+pub struct ProcessingStep3_10();
+
+impl ProcessingStep3_10 {
+    // Define an interface as a wrapper over the basemost data type,
+    // whose methods invoke the method of the data type implementing
+    // the interface, matching on the dynamic data type tag.
+    pub fn exec(&self, host: &mut SModelHost, interface: &Rc<SmInterface>, basemost: &Symbol, smodel_path: &proc_macro2::TokenStream) {
+        let attr = &interface.attributes;
+        let vis = &interface.visibility;
+        let name = &interface.name;
+        let interface_name = name.to_string();
+        let basemost_id = Ident::new(&basemost.name(), Span::call_site());
+        let tag_enum = tag_enum_name(basemost);
+        let data_tag_field = Ident::new(DATA_TAG_FIELD, Span::call_site());
+        let (impl_generics, ty_generics, where_clause) = host.split_generics();
+
+        // Group the non-abstract data types implementing the interface
+        // by the basemost data type listing the interface.
+        let mut implementors: Vec<(Symbol, Vec<Symbol>)> = vec![];
+        let mut smtype_list = vec![basemost.clone()];
+        smtype_list.extend(basemost.descendant_smtype_list());
+        for m in smtype_list.iter() {
+            if m.is_abstract() {
+                continue;
+            }
+            let Some(declarer) = m.asc_smtype_list().into_iter().find(|m1| m1.interfaces().includes(&interface_name)) else {
+                continue;
+            };
+            if let Some((_, tags)) = implementors.iter_mut().find(|(m1, _)| m1 == &declarer) {
+                tags.push(m.clone());
+            } else {
+                implementors.push((declarer, vec![m.clone()]));
+            }
+        }
+        let tags = implementors.iter().flat_map(|(_, tags)| tags.iter().map(|m| Ident::new(&m.name(), Span::call_site()))).collect::<Vec<_>>();
+
+        // Contribute the interface methods.
+        let mut methods = proc_macro2::TokenStream::new();
+        for method in interface.methods.iter() {
+            let method_attr = &method.attributes;
+            let method_name = &method.name;
+            let generics = &method.generics;
+            let method_where_clause = &method.generics.where_clause;
+            let result_annotation = method.result_type.as_ref().map(|t| quote! { -> #t });
+            let receiver = method.inputs.first().unwrap();
+
+            // Remove the receiver
+            let inputs = method.inputs.iter().skip(1).cloned().collect::<Punctuated<FnArg, Comma>>();
+            let (inputs, input_args) = bind_function_inputs(&inputs);
+
+            let mut arms = proc_macro2::TokenStream::new();
+            for (declarer, tags) in implementors.iter() {
+                let tags = tags.iter().map(|m| Ident::new(&m.name(), Span::call_site()));
+                let call = if declarer == basemost {
+                    quote! { self.0.#method_name(#input_args) }
+                } else {
+                    // Generate `MN(...M2(self.0.clone()))` layers from
+                    // the basemost data type to the declarer.
+                    let declarer_value = proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root("self.0", &declarer.asc_smtype_list()[1..])).unwrap();
+                    quote! { #declarer_value.#method_name(#input_args) }
+                };
+                arms.extend(quote! {
+                    #(#tag_enum::#tags)|* => #call,
+                });
+            }
+
+            methods.extend(quote! {
+                #(#method_attr)*
+                pub fn #method_name #generics(#receiver, #inputs) #result_annotation #method_where_clause {
+                    match self.0.#data_tag_field() {
+                        #arms
+                        #[allow(unreachable_patterns)]
+                        _ => unreachable!(),
+                    }
+                }
+            });
+        }
+
+        let from_object_arm = if tags.is_empty() {
+            proc_macro2::TokenStream::new()
+        } else {
+            quote! { #(#tag_enum::#tags)|* => Some(Self(object)), }
+        };
+
+        let debug_where_clause = host.where_clause_with(&[quote! { #basemost_id #ty_generics: ::std::fmt::Debug }]);
+
+        let mut conversions = proc_macro2::TokenStream::new();
+        for (declarer, _) in implementors.iter() {
+            for m in [declarer.clone()].into_iter().chain(declarer.descendant_smtype_list()) {
+                let m_id = Ident::new(&m.name(), Span::call_site());
+                let value = if &m == basemost { quote! { v } } else { quote! { v.into() } };
+                conversions.extend(quote! {
+                    impl #impl_generics From<#m_id #ty_generics> for #name #ty_generics #where_clause {
+                        fn from(v: #m_id #ty_generics) -> Self {
+                            Self(#value)
+                        }
+                    }
+                });
+            }
+        }
+
+        host.output.extend::<TokenStream>(quote! {
+            #(#attr)*
+            #vis struct #name #impl_generics (#basemost_id #ty_generics) #where_clause;
+
+            impl #impl_generics #name #ty_generics #where_clause {
+                #methods
+            }
+
+            impl #impl_generics Clone for #name #ty_generics #where_clause {
+                fn clone(&self) -> Self {
+                    Self(self.0.clone())
+                }
+            }
+
+            impl #impl_generics PartialEq for #name #ty_generics #where_clause {
+                fn eq(&self, other: &Self) -> bool {
+                    self.0 == other.0
+                }
+            }
+
+            impl #impl_generics Eq for #name #ty_generics #where_clause {}
+
+            impl #impl_generics ::std::hash::Hash for #name #ty_generics #where_clause {
+                fn hash<__H: ::std::hash::Hasher>(&self, state: &mut __H) {
+                    self.0.hash(state);
+                }
+            }
+
+            impl #impl_generics ::std::fmt::Debug for #name #ty_generics #debug_where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    ::std::fmt::Debug::fmt(&self.0, f)
+                }
+            }
+
+            impl #impl_generics ::std::ops::Deref for #name #ty_generics #where_clause {
+                type Target = #basemost_id #ty_generics;
+                fn deref(&self) -> &Self::Target {
+                    &self.0
+                }
+            }
+
+            impl #impl_generics #smodel_path::Interface<#basemost_id #ty_generics> for #name #ty_generics #where_clause {
+                fn from_object(object: #basemost_id #ty_generics) -> Option<Self> {
+                    match object.#data_tag_field() {
+                        #from_object_arm
+                        _ => None,
+                    }
+                }
+            }
+
+            #conversions
+
+            impl #impl_generics From<#name #ty_generics> for #basemost_id #ty_generics #where_clause {
+                fn from(v: #name #ty_generics) -> Self {
+                    v.0
+                }
+            }
+        }.try_into().unwrap());
+
+        // Contribute an `as_<interface>` conversion to the basemost data type.
+        let as_name = Ident::new(&format!("as_{}", to_snake_case(&interface_name)), Span::call_site());
+        let doc = format!("Returns the object as the interface `{interface_name}`, if it implements it.");
+        basemost.method_output().borrow_mut().extend(quote! {
+            #[doc = #doc]
+            pub fn #as_name(&self) -> Option<#name #ty_generics> {
+                <#name #ty_generics as #smodel_path::Interface<Self>>::from_object(self.clone())
+            }
+        });
+    }
+}
@@ -0,0 +1,77 @@
+use crate::*;
+
+pub struct ProcessingStep3_11();
+
+impl ProcessingStep3_11 {
+    // Contribute the `#FirstM#VISITOR_SUFFIX` trait, with a `visit_m` method
+    // per data type defaulting to the base data type's method, and
+    // an `accept` method to the basemost data type dispatching once
+    // to the most specific `visit_m` method.
+    pub fn exec(&self, host: &mut SModelHost, data_types: &[Rc<SmType>], basemost: &Symbol) {
+        let basemost_name = basemost.name();
+        let visitor_trait = Ident::new(&format!("{basemost_name}{VISITOR_SUFFIX}"), Span::call_site());
+        let visitor_vis = data_types[0].visibility.clone();
+        let tag_enum = tag_enum_name(basemost);
+        let data_tag_field = Ident::new(DATA_TAG_FIELD, Span::call_site());
+        let (impl_generics, ty_generics, where_clause) = host.split_generics();
+
+        let mut visit_methods = proc_macro2::TokenStream::new();
+        let mut arms = proc_macro2::TokenStream::new();
+        for smtype_node in data_types.iter() {
+            let Some(smtype) = host.semantics.get(smtype_node) else {
+                continue;
+            };
+            let smtype_name_id = Ident::new(&smtype.name(), Span::call_site());
+            let visit_name = Ident::new(&format!("visit_{}", to_snake_case(&smtype.name())), Span::call_site());
+            let doc = format!("Visits a `{}`.", smtype.name());
+            let body = if let Some(base) = smtype.inherits() {
+                let base_visit_name = Ident::new(&format!("visit_{}", to_snake_case(&base.name())), Span::call_site());
+                quote! { self.#base_visit_name(node); }
+            } else {
+                proc_macro2::TokenStream::new()
+            };
+            visit_methods.extend(quote! {
+                #[doc = #doc]
+                #[allow(unused_variables)]
+                fn #visit_name(&mut self, node: &#smtype_name_id #ty_generics) {
+                    #body
+                }
+            });
+
+            if smtype.is_abstract() {
+                continue;
+            }
+            // Generate `MN(...M2(self.clone()))` layers from
+            // the basemost data type to the data type.
+            let node = if &smtype == basemost {
+                quote! { self }
+            } else {
+                let layers = proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root("self", &smtype.asc_smtype_list()[1..])).unwrap();
+                quote! { &#layers }
+            };
+            arms.extend(quote! {
+                #tag_enum::#smtype_name_id => v.#visit_name(#node),
+            });
+        }
+
+        let doc = format!("Visits objects of a `{basemost_name}` model. Each visit method defaults to the visit method of the base data type.");
+        host.output.extend::<TokenStream>(quote! {
+            #[doc = #doc]
+            #visitor_vis trait #visitor_trait #impl_generics #where_clause {
+                #visit_methods
+            }
+        }.try_into().unwrap());
+
+        basemost.method_output().borrow_mut().extend(quote! {
+            /// Invokes the most specific visit method of the visitor
+            /// for the data type the object was constructed as.
+            pub fn accept(&self, v: &mut impl #visitor_trait #ty_generics) {
+                match self.#data_tag_field() {
+                    #arms
+                    #[allow(unreachable_patterns)]
+                    _ => {},
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,180 @@
+use crate::*;
+
+pub struct ProcessingStep3_12();
+
+impl ProcessingStep3_12 {
+    // Contribute an associated constant to each data type for each constant
+    // or static member it declares or inherits, and a method reading
+    // the member of the data type an instance was constructed as
+    // to the data type declaring the member.
+    pub fn exec(&self, host: &mut SModelHost, data_types: &[Rc<SmType>]) -> bool {
+        let data_id = Ident::new(DATA, Span::call_site());
+        let data_tag_field = Ident::new(DATA_TAG_FIELD, Span::call_site());
+        let (_, ty_generics, _) = host.split_generics();
+
+        // The most specific declaration of each member, along with
+        // the data type declaring it, for each data type.
+        let mut members: HashMap<String, Vec<(Rc<SmTypeConst>, Symbol)>> = HashMap::new();
+
+        for smtype_node in data_types.iter() {
+            let Some(smtype) = host.semantics.get(smtype_node) else {
+                continue;
+            };
+            let tag_enum = tag_enum_name(&smtype);
+            let mut smtype_members = smtype.inherits().and_then(|m| members.get(&m.name()).cloned()).unwrap_or_default();
+            let mut own_names: Vec<String> = vec![];
+
+            for node in smtype_node.consts.iter() {
+                let name = node.name.to_string();
+                if own_names.contains(&name) {
+                    node.name.span().unwrap().error(format!("Redefining '{name}'")).emit();
+                    return false;
+                }
+                own_names.push(name.clone());
+
+                let base = smtype_members.iter().position(|(c, _)| c.name == node.name);
+                if !self.check_override(node, base.map(|i| &smtype_members[i])) {
+                    return false;
+                }
+                if let Some(i) = base {
+                    smtype_members[i] = (node.clone(), smtype.clone());
+                } else {
+                    smtype_members.push((node.clone(), smtype.clone()));
+                }
+
+                // Contribute the static item of a static member.
+                if node.is_static {
+                    let static_name = Ident::new(&format!("{DATA_STATIC_PREFIX}{}_{name}", smtype.name()), Span::call_site());
+                    let type_annotation = &node.type_annotation;
+                    let value = &node.value;
+                    host.data_output.extend(quote! {
+                        #[allow(non_upper_case_globals)]
+                        pub static #static_name: #type_annotation = #value;
+                    });
+                }
+
+                if node.is_override {
+                    continue;
+                }
+
+                // Contribute a method reading the member of the data type
+                // an instance was constructed as, matching on its tag.
+                let name_id = &node.name;
+                let mut arms = proc_macro2::TokenStream::new();
+                for m in [smtype.clone()].into_iter().chain(smtype.descendant_smtype_list()) {
+                    if m.is_abstract() {
+                        continue;
+                    }
+                    let m_name = Ident::new(&m.name(), Span::call_site());
+                    arms.extend(quote! {
+                        #tag_enum::#m_name => <#m_name #ty_generics>::#name_id,
+                    });
+                }
+                let vis = &node.visibility;
+                let reader_name_str = format!("{}_{}", name.to_lowercase(), if node.is_static { "static" } else { "const" });
+                if RESERVED_METHOD_NAMES.contains(&reader_name_str.as_str()) {
+                    node.name.span().unwrap().error(format!("Member '{name}' is read by a generated '{reader_name_str}' method, whose name is reserved.")).emit();
+                    return false;
+                }
+                if let Some(defined_in) = self.lookup_member_accessor(&smtype, &reader_name_str) {
+                    node.name.span().unwrap().error(format!("Member '{name}' is read by a generated '{reader_name_str}' method, which is already defined in '{defined_in}'.")).emit();
+                    return false;
+                }
+                let reader_name = Ident::new(&reader_name_str, Span::call_site());
+                let type_annotation = &node.type_annotation;
+                let result_type = if node.is_static { quote! { &'static #type_annotation } } else { quote! { #type_annotation } };
+                let doc = format!("Returns `{name}` of the data type the object was constructed as.");
+                smtype.method_output().borrow_mut().extend(quote! {
+                    #[doc = #doc]
+                    #vis fn #reader_name(&self) -> #result_type {
+                        match self.#data_tag_field() {
+                            #arms
+                            #[allow(unreachable_patterns)]
+                            _ => unreachable!(),
+                        }
+                    }
+                });
+            }
+
+            // Contribute the associated constants, either
+            // declared or inherited by the data type.
+            for (node, defined_in) in smtype_members.iter() {
+                let attr = &node.attributes;
+                let vis = &node.visibility;
+                let name = &node.name;
+                let type_annotation = &node.type_annotation;
+                let (type_annotation, value) = if &smtype != defined_in {
+                    let defined_in_id = Ident::new(&defined_in.name(), Span::call_site());
+                    let type_annotation = if node.is_static { quote! { &'static #type_annotation } } else { quote! { #type_annotation } };
+                    (type_annotation, quote! { <#defined_in_id #ty_generics>::#name })
+                } else if node.is_static {
+                    let static_name = Ident::new(&format!("{DATA_STATIC_PREFIX}{}_{name}", smtype.name()), Span::call_site());
+                    (quote! { &'static #type_annotation }, quote! { &#data_id::#static_name })
+                } else {
+                    let value = &node.value;
+                    (quote! { #type_annotation }, quote! { #value })
+                };
+                smtype.method_output().borrow_mut().extend(quote! {
+                    #(#attr)*
+                    #vis const #name: #type_annotation = #value;
+                });
+            }
+
+            members.insert(smtype.name(), smtype_members);
+        }
+
+        true
+    }
+
+    // Returns the name of the data type, either the given one, a base
+    // data type or a subtype, defining a method or field accessor of
+    // a given name.
+    fn lookup_member_accessor(&self, smtype: &Symbol, name: &str) -> Option<String> {
+        for m in smtype.asc_smtype_list().into_iter().chain(smtype.descendant_smtype_list()) {
+            let is_field_accessor = m.fields().borrow().iter().any(|(f, _)| {
+                [f.clone(), format!("set_{f}"), format!("try_get_{f}"), format!("try_set_{f}")].iter().any(|a| a == name)
+            });
+            if is_field_accessor || m.methods().has(&name.to_owned()) {
+                return Some(m.name());
+            }
+        }
+        None
+    }
+
+    // Ensure a member marked `override` overrides a member of a base
+    // data type of the same kind, visibility and type, and that
+    // other members do not override one.
+    fn check_override(&self, node: &SmTypeConst, base: Option<&(Rc<SmTypeConst>, Symbol)>) -> bool {
+        let Some((base_node, base_smtype)) = base else {
+            if node.is_override {
+                node.name.span().unwrap().error(format!("Member '{}' does not override a member of a base data type.", node.name)).emit();
+                return false;
+            }
+            return true;
+        };
+        let base_name = base_smtype.name();
+        if !node.is_override {
+            node.name.span().unwrap().error(format!("Member '{}' is already defined in '{base_name}'; mark it `override` to override it.", node.name)).emit();
+            return false;
+        }
+        if node.is_static != base_node.is_static {
+            let expected = if base_node.is_static { "static" } else { "const" };
+            node.name.span().unwrap().error(format!("Member must be `{expected}`, as in '{base_name}'.")).emit();
+            return false;
+        }
+        let visibility = tokens_to_string(&node.visibility);
+        let base_visibility = tokens_to_string(&base_node.visibility);
+        if visibility != base_visibility {
+            let expected = if base_visibility.is_empty() { "private".to_owned() } else { format!("'{base_visibility}'") };
+            node.name.span().unwrap().error(format!("Visibility must be {expected}, as in '{base_name}'.")).emit();
+            return false;
+        }
+        let type_annotation = tokens_to_string(&node.type_annotation);
+        let base_type_annotation = tokens_to_string(&base_node.type_annotation);
+        if type_annotation != base_type_annotation {
+            node.type_annotation.span().unwrap().error(format!("Member type must be '{base_type_annotation}', as in '{base_name}'.")).emit();
+            return false;
+        }
+        true
+    }
+}
@@ -3,7 +3,7 @@ use crate::*;
 pub struct ProcessingStep3_2();
 
 impl ProcessingStep3_2 {
-    pub fn exec(&self, host: &mut SModelHost, smtype: &Symbol, field: &Rc<SmTypeField>, base_accessor: &str, asc_smtype_list: &[Symbol], field_output: &mut proc_macro2::TokenStream) -> bool {
+    pub fn exec(&self, host: &mut SModelHost, smtype: &Symbol, field: &Rc<SmTypeField>, base_accessor: &str, asc_smtype_list: &[Symbol], field_output: &mut proc_macro2::TokenStream, smodel_path: &proc_macro2::TokenStream) -> bool {
         // 1. Create a FieldSlot.
         let slot = host.factory.create_field_slot(field.is_ref, field.name.to_string(), field.type_annotation.clone(), field.default_value.clone());
 
@@ -15,6 +15,11 @@ impl ProcessingStep3_2 {
             smtype.fields().set(slot.name(), slot.clone());
         }
 
+        if RESERVED_METHOD_NAMES.contains(&slot.name().as_str()) {
+            field.name.span().unwrap().error(format!("Field name '{}' is reserved for a generated method.", slot.name())).emit();
+            return false;
+        }
+
         // 3. Contribute a field to the #DATA::M structure.
         let field_name = slot.name();
         let field_name_id = Ident::new(&field_name, Span::call_site());
@@ -30,43 +35,89 @@ impl ProcessingStep3_2 {
         }
 
         // 4. Define accessors
-        self.define_accessors(host, smtype, &slot, &field_name, &field_type, base_accessor, asc_smtype_list);
+        self.define_accessors(host, smtype, field, base_accessor, asc_smtype_list, smodel_path);
 
         true
     }
 
-    fn define_accessors(&self, _host: &mut SModelHost, smtype: &Symbol, slot: &Symbol, field_name: &str, field_type: &Type, base_accessor: &str, asc_smtype_list: &[Symbol]) {
+    fn define_accessors(&self, host: &mut SModelHost, smtype: &Symbol, field: &SmTypeField, base_accessor: &str, asc_smtype_list: &[Symbol], smodel_path: &proc_macro2::TokenStream) {
+        let attr = &field.attributes;
+        let getter_vis = &field.getter_visibility;
+        let setter_vis = &field.setter_visibility;
+        let field_name = &field.name.to_string();
+        let field_type = &field.type_annotation;
         let getter_name = Ident::new(&field_name, Span::call_site());
         let setter_name = Ident::new(&format!("set_{}", field_name), Span::call_site());
-        let fv = proc_macro2::TokenStream::from_str(&self.match_field(asc_smtype_list, 0, &format!("{base_accessor}.upgrade().unwrap()"), field_name)).unwrap();
+        let try_getter_name = Ident::new(&format!("try_get_{}", field_name), Span::call_site());
+        let try_setter_name = Ident::new(&format!("try_set_{}", field_name), Span::call_site());
+        let upgraded = host.upgrade_weak_root(base_accessor, &format!("field '{field_name}' of '{}'", smtype.name()));
+        let fv = proc_macro2::TokenStream::from_str(&self.match_field(asc_smtype_list, 0, &upgraded, field_name)).unwrap();
 
-        if slot.is_ref() {
+        // Checked accessors, returning `Err(SModelError::Dangling)`
+        // if the arena was dropped.
+        let base_accessor = proc_macro2::TokenStream::from_str(base_accessor).unwrap();
+        let checked_fv = proc_macro2::TokenStream::from_str(&self.match_field(asc_smtype_list, 0, "__root", field_name)).unwrap();
+        let upgrade_checked = quote! {
+            let Some(__root) = #base_accessor.upgrade() else {
+                return Err(#smodel_path::SModelError::Dangling);
+            };
+        };
+
+        if field.is_ref {
             smtype.method_output().borrow_mut().extend(quote! {
+                #(#attr)*
                 #[allow(non_snake_case)]
-                fn #getter_name(&self) -> #field_type {
+                #getter_vis fn #getter_name(&self) -> #field_type {
                     #fv.borrow().clone()
                 }
                 #[allow(non_snake_case)]
-                fn #setter_name(&self, v: #field_type) {
+                #setter_vis fn #setter_name(&self, v: #field_type) {
                     #fv.replace(v);
                 }
+                #[allow(non_snake_case)]
+                #getter_vis fn #try_getter_name(&self) -> Result<#field_type, #smodel_path::SModelError> {
+                    #upgrade_checked
+                    let v = #checked_fv.borrow().clone();
+                    Ok(v)
+                }
+                #[allow(non_snake_case)]
+                #setter_vis fn #try_setter_name(&self, v: #field_type) -> Result<(), #smodel_path::SModelError> {
+                    #upgrade_checked
+                    #checked_fv.replace(v);
+                    Ok(())
+                }
             });
         } else {
             smtype.method_output().borrow_mut().extend(quote! {
+                #(#attr)*
                 #[allow(non_snake_case)]
-                fn #getter_name(&self) -> #field_type {
+                #getter_vis fn #getter_name(&self) -> #field_type {
                     #fv.get()
                 }
 
                 #[allow(non_snake_case)]
-                fn #setter_name(&self, v: #field_type) {
+                #setter_vis fn #setter_name(&self, v: #field_type) {
                     #fv.set(v);
                 }
+
+                #[allow(non_snake_case)]
+                #getter_vis fn #try_getter_name(&self) -> Result<#field_type, #smodel_path::SModelError> {
+                    #upgrade_checked
+                    Ok(#checked_fv.get())
+                }
+
+                #[allow(non_snake_case)]
+                #setter_vis fn #try_setter_name(&self, v: #field_type) -> Result<(), #smodel_path::SModelError> {
+                    #upgrade_checked
+                    #checked_fv.set(v);
+                    Ok(())
+                }
             });
         }
     }
 
-    /// Matches a field. `base` is assumed to be a `Rc<#DATA::M>` value.
+    /// Matches a field. `base` is assumed to be a `Rc<#DATA::M>` value,
+    /// or a `#DATA::M` value in the flat layout.
     fn match_field(&self, asc_smtype_list: &[Symbol], smtype_index: usize, base: &str, field_name: &str) -> String {
         let (smtype, inherited) = if smtype_index + 1 >= asc_smtype_list.len() {
             (asc_smtype_list[smtype_index].clone(), None)
@@ -4,35 +4,64 @@ pub struct ProcessingStep3_6();
 
 impl ProcessingStep3_6 {
     pub fn exec(&self, host: &mut SModelHost, node: &Rc<SmType>, smtype: &Symbol, base_accessor: &str, smodel_path: &proc_macro2::TokenStream) {
-        let smtype_name_debug = format!("{}()", smtype.name());
+        let smtype_name_str = smtype.name();
         let smtype_name = node.name.clone();
         let attributes = node.attributes.clone();
         let visi = node.visibility.clone();
+        let (impl_generics, ty_generics, where_clause) = host.split_generics();
 
         // Define the structure M, as in
         //
         // ```
-        // #[derive(Clone)]
-        // struct M(Weak<#DATA::M>);
+        // struct M(Weak<#DATA::M>, usize);
         // ```
         //
         // or as in:
         //
         // ```
-        // #[derive(Clone, PartialEq, Hash)]
         // struct M(InheritedM);
         // ```
         //
-        // if there is an inherited base.
+        // if there is an inherited base. Clone, PartialEq, Hash, PartialOrd
+        // and Ord are implemented without bounds on generic parameters.
         if let Some(inherits) = smtype.inherits() {
             let inherited_name = Ident::new(&inherits.name(), Span::call_site());
             host.output.extend::<TokenStream>(quote! {
                 #(#attributes)*
-                #[derive(Clone, PartialEq, Hash)]
-                #visi struct #smtype_name(#inherited_name);
+                #visi struct #smtype_name #impl_generics (#inherited_name #ty_generics) #where_clause;
 
-                impl ::std::ops::Deref for #smtype_name {
-                    type Target = #inherited_name;
+                impl #impl_generics Clone for #smtype_name #ty_generics #where_clause {
+                    fn clone(&self) -> Self {
+                        Self(self.0.clone())
+                    }
+                }
+
+                impl #impl_generics PartialEq for #smtype_name #ty_generics #where_clause {
+                    fn eq(&self, other: &Self) -> bool {
+                        self.0 == other.0
+                    }
+                }
+
+                impl #impl_generics ::std::hash::Hash for #smtype_name #ty_generics #where_clause {
+                    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                        self.0.hash(state)
+                    }
+                }
+
+                impl #impl_generics PartialOrd for #smtype_name #ty_generics #where_clause {
+                    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+                        Some(self.cmp(other))
+                    }
+                }
+
+                impl #impl_generics Ord for #smtype_name #ty_generics #where_clause {
+                    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                        self.0.cmp(&other.0)
+                    }
+                }
+
+                impl #impl_generics ::std::ops::Deref for #smtype_name #ty_generics #where_clause {
+                    type Target = #inherited_name #ty_generics;
                     fn deref(&self) -> &Self::Target {
                         &self.0
                     }
@@ -41,46 +70,167 @@ impl ProcessingStep3_6 {
         } else {
             let data_id = Ident::new(DATA, Span::call_site());
             let smtype_data_name = Ident::new(&format!("{DATA_PREFIX}{}", smtype.name()), Span::call_site());
+            let data_id_field = Ident::new(DATA_ID_FIELD, Span::call_site());
+
+            // The object ID is held along with the data, so that
+            // hashing and ordering never access the data.
             host.output.extend::<TokenStream>(quote! {
                 #(#attributes)*
-                #[derive(Clone)]
-                #visi struct #smtype_name(::std::rc::Weak<#data_id::#smtype_data_name>);
+                #visi struct #smtype_name #impl_generics (::std::rc::Weak<#data_id::#smtype_data_name #ty_generics>, usize) #where_clause;
+
+                impl #impl_generics Clone for #smtype_name #ty_generics #where_clause {
+                    fn clone(&self) -> Self {
+                        Self(self.0.clone(), self.1)
+                    }
+                }
 
-                impl PartialEq for #smtype_name {
+                impl #impl_generics PartialEq for #smtype_name #ty_generics #where_clause {
                     fn eq(&self, other: &Self) -> bool {
                         self.0.ptr_eq(&other.0)
                     }
                 }
 
-                impl ::std::hash::Hash for #smtype_name {
+                impl #impl_generics ::std::hash::Hash for #smtype_name #ty_generics #where_clause {
                     fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
-                        self.0.as_ptr().hash(state)
+                        self.1.hash(state)
+                    }
+                }
+
+                impl #impl_generics PartialOrd for #smtype_name #ty_generics #where_clause {
+                    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+                        Some(self.cmp(other))
+                    }
+                }
+
+                impl #impl_generics Ord for #smtype_name #ty_generics #where_clause {
+                    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                        // Objects from different arenas may share an ID.
+                        self.1.cmp(&other.1).then_with(|| self.0.as_ptr().cmp(&other.0.as_ptr()))
+                    }
+                }
+
+                impl #impl_generics #smodel_path::ArenaData<#smtype_name #ty_generics> for #data_id::#smtype_data_name #ty_generics #where_clause {
+                    fn set_id(&mut self, id: usize) {
+                        self.#data_id_field = id;
+                    }
+                    fn handle(data: &::std::rc::Rc<Self>) -> #smtype_name #ty_generics {
+                        #smtype_name(::std::rc::Rc::downgrade(data), data.#data_id_field)
                     }
                 }
             }.try_into().unwrap());
-        }
 
-        // Implement Eq and Debug
-        host.output.extend::<TokenStream>(quote! {
-            impl Eq for #smtype_name {}
+            // Contribute methods returning the object ID and
+            // whether the arena of the object is still alive.
+            smtype.method_output().borrow_mut().extend(quote! {
+                /// Returns the object ID, which is unique within the arena.
+                pub fn id(&self) -> usize {
+                    self.1
+                }
+
+                /// Returns whether the arena of the object is still alive.
+                pub fn is_alive(&self) -> bool {
+                    self.0.strong_count() != 0
+                }
+            });
+
+            // Contribute methods returning the tag and name of the data type
+            // an instance was constructed as.
+            let tag_enum = tag_enum_name(smtype);
+            let data_tag_field = Ident::new(DATA_TAG_FIELD, Span::call_site());
+            let upgraded = proc_macro2::TokenStream::from_str(&host.upgrade_weak_root("self.0", &format!("the data type of '{}'", smtype.name()))).unwrap();
+            smtype.method_output().borrow_mut().extend(quote! {
+                fn #data_tag_field(&self) -> #tag_enum {
+                    #upgraded.#data_tag_field
+                }
+
+                /// Returns the tag of the data type the object was constructed as.
+                /// Use `as_kind` for a handle of that data type.
+                pub fn kind(&self) -> #tag_enum {
+                    self.#data_tag_field()
+                }
+
+                /// Returns the name of the data type the object was constructed as.
+                pub fn type_name(&self) -> &'static str {
+                    self.#data_tag_field().name()
+                }
+            });
 
-            impl ::std::fmt::Debug for #smtype_name {
-                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-                    write!(f, #smtype_name_debug)
+            // Contribute the `#FirstM#KIND_SUFFIX` enumeration, holding
+            // a handle of the data type an instance was constructed as,
+            // and a method converting to it.
+            let kind_enum = Ident::new(&format!("{smtype_name_str}{KIND_SUFFIX}"), Span::call_site());
+            let kind_enum_doc = format!("Holds a `{smtype_name_str}` as the data type it was constructed as.");
+            let mut variants = proc_macro2::TokenStream::new();
+            let mut arms = proc_macro2::TokenStream::new();
+            for m in [smtype.clone()].into_iter().chain(smtype.descendant_smtype_list()) {
+                if m.is_abstract() {
+                    continue;
                 }
+                let m_name = Ident::new(&m.name(), Span::call_site());
+                let layers = proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root("self", &m.asc_smtype_list()[1..])).unwrap();
+                variants.extend(quote! { #m_name(#m_name #ty_generics), });
+                arms.extend(quote! { #tag_enum::#m_name => #kind_enum::#m_name(#layers), });
+            }
+            host.output.extend::<TokenStream>(quote! {
+                #[doc = #kind_enum_doc]
+                #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+                #visi enum #kind_enum #impl_generics #where_clause {
+                    #variants
+                }
+            }.try_into().unwrap());
+            smtype.method_output().borrow_mut().extend(quote! {
+                /// Returns the object as the data type it was constructed as,
+                /// for exhaustive matching. Use `kind` for the tag only.
+                pub fn as_kind(&self) -> #kind_enum #ty_generics {
+                    match self.#data_tag_field() {
+                        #arms
+                    }
+                }
+            });
+        }
+
+        // Contribute a method returning the static data type name.
+        smtype.method_output().borrow_mut().extend(quote! {
+            /// Returns the name of the data type.
+            pub fn static_type_name() -> &'static str {
+                #smtype_name_str
             }
+        });
+
+        // Implement Eq.
+        host.output.extend::<TokenStream>(quote! {
+            impl #impl_generics Eq for #smtype_name #ty_generics #where_clause {}
         }.try_into().unwrap());
 
+        // Implement Debug, writing the name of the data type the object
+        // was constructed as, or the static name if the arena was dropped.
+        //
+        // A field-aware Debug is implemented at a later step
+        // if the data type or one of its base data types has
+        // the `debug` option. Debug is not implemented if the data
+        // type has an `impl Debug` block.
+        let implements_debug = node.impls.iter().any(|imp| imp.is_debug_trait());
+        if !implements_debug && !smtype.asc_smtype_list().iter().any(|m| m.is_debug()) {
+            host.output.extend::<TokenStream>(quote! {
+                impl #impl_generics ::std::fmt::Debug for #smtype_name #ty_generics #where_clause {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        let name = if self.is_alive() { self.type_name() } else { #smtype_name_str };
+                        write!(f, "{name}()")
+                    }
+                }
+            }.try_into().unwrap());
+        }
+
         // Output From<M> for InheritedM implementation (covariant conversion)
-        let mut base = "v.0.0".to_owned();
+        let mut base = "v.0".to_owned();
         let mut m = smtype.clone();
         while let Some(m1) = m.inherits() {
             let inherited_name = Ident::new(&m1.name(), Span::call_site());
             let base_tokens = proc_macro2::TokenStream::from_str(&base).unwrap();
             host.output.extend::<TokenStream>(quote! {
-                impl From<#smtype_name> for #inherited_name {
-                    fn from(v: #smtype_name) -> Self {
-                        #inherited_name(#base_tokens.clone())
+                impl #impl_generics From<#smtype_name #ty_generics> for #inherited_name #ty_generics #where_clause {
+                    fn from(v: #smtype_name #ty_generics) -> Self {
+                        #base_tokens.clone()
                     }
                 }
             }.try_into().unwrap());
@@ -92,17 +242,43 @@ impl ProcessingStep3_6 {
         for sm in smtype.subtypes().iter() {
             self.contravariance(host, &base_accessor.replacen("self", "v", 1), smtype, &sm, smodel_path);
         }
+
+        // Output an ArenaSubtype<#DATA::FirstM> for M implementation, converting
+        // an object allocated in the arena to M.
+        let basemost = smtype.asc_smtype_list()[0].clone();
+        let basemost_name = Ident::new(&basemost.name(), Span::call_site());
+        let basemost_data_name = Ident::new(&format!("{DATA_PREFIX}{}", basemost.name()), Span::call_site());
+        let data_id = Ident::new(DATA, Span::call_site());
+        let handle = quote! { <#data_id::#basemost_data_name #ty_generics as #smodel_path::ArenaData<#basemost_name #ty_generics>>::handle(data) };
+        let conversion = if smtype == &basemost {
+            quote! { Some(#handle) }
+        } else {
+            quote! { #smtype_name::try_from(#handle).ok() }
+        };
+        host.output.extend::<TokenStream>(quote! {
+            impl #impl_generics #smodel_path::ArenaSubtype<#data_id::#basemost_data_name #ty_generics> for #smtype_name #ty_generics #where_clause {
+                fn from_arena_data(data: &::std::rc::Rc<#data_id::#basemost_data_name #ty_generics>) -> Option<Self> {
+                    #conversion
+                }
+            }
+        }.try_into().unwrap());
     }
 
     fn contravariance(&self, host: &mut SModelHost, base_accessor: &str, base_smtype: &Symbol, subtype: &Symbol, smodel_path: &proc_macro2::TokenStream) {
         let base_smtype_name = Ident::new(&base_smtype.name(), Span::call_site());
         let subtype_name = Ident::new(&subtype.name(), Span::call_site());
-        let m = proc_macro2::TokenStream::from_str(&self.match_contravariant(&subtype.asc_smtype_list(), 0, &format!("{base_accessor}.upgrade().unwrap()"), &base_accessor, smodel_path)).unwrap();
+        let original_base = base_accessor.strip_suffix(".0").unwrap();
+        let m = proc_macro2::TokenStream::from_str(&self.match_contravariant(&subtype.asc_smtype_list(), 0, "__root", original_base, &base_smtype.name(), smodel_path)).unwrap();
+        let base_accessor_tokens = proc_macro2::TokenStream::from_str(base_accessor).unwrap();
+        let (impl_generics, ty_generics, where_clause) = host.split_generics();
 
         host.output.extend::<TokenStream>(quote! {
-            impl TryFrom<#base_smtype_name> for #subtype_name {
+            impl #impl_generics TryFrom<#base_smtype_name #ty_generics> for #subtype_name #ty_generics #where_clause {
                 type Error = #smodel_path::SModelError;
-                fn try_from(v: #base_smtype_name) -> Result<Self, Self::Error> {
+                fn try_from(v: #base_smtype_name #ty_generics) -> Result<Self, Self::Error> {
+                    let Some(__root) = #base_accessor_tokens.upgrade() else {
+                        return Err(#smodel_path::SModelError::Dangling);
+                    };
                     #m
                 }
             }
@@ -115,9 +291,11 @@ impl ProcessingStep3_6 {
 
     /// Matches a contravariant type.
     /// 
-    /// * `base` is assumed to be a `Rc<#DATA::M>` value.
-    /// * `original_base` is assumed to be a `Weak<#DATA::FirstM>` value.
-    fn match_contravariant(&self, asc_smtype_list: &[Symbol], smtype_index: usize, base: &str, original_base: &str, smodel_path: &proc_macro2::TokenStream) -> String {
+    /// * `base` is assumed to be a `Rc<#DATA::M>` value, or a `#DATA::M` value in the flat layout.
+    /// * `original_base` is assumed to be a `FirstM` value.
+    /// * `from` is the name of the data type converted from.
+    /// * The `__root` variable is assumed to be a `Rc<#DATA::FirstM>` value.
+    fn match_contravariant(&self, asc_smtype_list: &[Symbol], smtype_index: usize, base: &str, original_base: &str, from: &str, smodel_path: &proc_macro2::TokenStream) -> String {
         let (smtype, inherited) = if smtype_index + 1 >= asc_smtype_list.len() {
             (asc_smtype_list[smtype_index].clone(), None)
         } else {
@@ -125,12 +303,13 @@ impl ProcessingStep3_6 {
         };
 
         let Some(inherited) = inherited else {
-            return format!("Ok({})", Symbol::create_layers_over_weak_root(original_base, asc_smtype_list));
+            return format!("Ok({})", Symbol::create_layers_over_weak_root(original_base, &asc_smtype_list[1..]));
         };
-        format!("if let {DATA}::{}::{}(_o) = &{base}.{DATA_VARIANT_FIELD} {{ {} }} else {{ Err({}::SModelError::Contravariant) }}",
+        format!("if let {DATA}::{}::{}(_o) = &{base}.{DATA_VARIANT_FIELD} {{ {} }} else {{ Err({}::SModelError::Contravariant {{ from: {from:?}, to: {:?}, actual: __root.{DATA_TAG_FIELD}.name() }}) }}",
             DATA_VARIANT_PREFIX.to_owned() + &inherited.name(),
             DATA_PREFIX.to_owned() + &smtype.name(),
-            self.match_contravariant(asc_smtype_list, smtype_index + 1, "_o", original_base, smodel_path),
-            smodel_path.to_string())
+            self.match_contravariant(asc_smtype_list, smtype_index + 1, "_o", original_base, from, smodel_path),
+            smodel_path.to_string(),
+            asc_smtype_list.last().unwrap().name())
     }
 }
\ No newline at end of file
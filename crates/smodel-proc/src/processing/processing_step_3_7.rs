@@ -6,7 +6,7 @@ pub struct ProcessingStep3_7();
 
 impl ProcessingStep3_7 {
     // Define the constructor
-    pub fn exec(&self, _host: &mut SModelHost, node: Option<&SmTypeConstructor>, smtype: &Symbol, asc_smtype_list: &[Symbol], arena_type_name: &str) {
+    pub fn exec(&self, host: &mut SModelHost, node: Option<&SmTypeConstructor>, smtype: &Symbol, asc_smtype_list: &[Symbol], arena_type_name: &str) {
         let input = node.map(|node| node.inputs.clone()).unwrap_or(Punctuated::new());
         let type_params = node.map(|node| [node.generics.lt_token.to_token_stream(), node.generics.params.to_token_stream(), node.generics.gt_token.to_token_stream()]).unwrap_or([
             proc_macro2::TokenStream::new(),
@@ -19,25 +19,35 @@ impl ProcessingStep3_7 {
 
         let ctor_init_name_id = Ident::new(CTOR_INIT_NAME, node.map(|node| node.name.span()).unwrap_or(Span::call_site()));
         let arena_type_name_id = Ident::new(arena_type_name, Span::call_site());
+        let (_, ty_generics, _) = host.split_generics();
 
         // Define the the instance `#ctor_init_name_id` method,
         // containing everything but `super()` and structure initialization.
+        // Parameters may be used only by `super()`, which is not part of it.
         let statements = node.map(|node| node.statements.clone()).unwrap_or(vec![]);
         smtype.method_output().borrow_mut().extend(quote! {
             #(#attr)*
+            #[allow(unused_variables)]
             fn #ctor_init_name_id #(#type_params)*(&self, #input) #where_clause {
                 #(#statements)*
             }
         });
 
+        // An abstract data type is never instantiated directly,
+        // therefore it has no `M::new` method.
+        if smtype.is_abstract() {
+            return;
+        }
+
         // `M::new` output
         let mut m_new_out = TokenStream::new();
 
-        // At `M::new`, let `__cto1` be a complex `M2(M1(__arena.allocate(#DATA::M1 { ... })))`
+        // At `M::new`, let `__cto1` be a complex `M3(M2(__arena.allocate::<M1>(#DATA::M1 { ... })))`
         // (notice the data type layers) allocation initializing all data type variants's fields
         // with their default values.
-        let initlayer1 = self.init_data(asc_smtype_list, 0);
-        let initlayer2 = proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root(&format!("arena.allocate({})", initlayer1.to_string()), asc_smtype_list)).unwrap();
+        let initlayer1 = self.init_data(host, asc_smtype_list, 0);
+        let basemost_name = format!("{}{}", asc_smtype_list[0].name(), ty_generics.to_token_stream());
+        let initlayer2 = proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root(&format!("arena.allocate::<{basemost_name}>({})", initlayer1.to_string()), &asc_smtype_list[1..])).unwrap();
         m_new_out.extend::<TokenStream>(quote! {
             let __cto1 = #initlayer2;
         }.try_into().unwrap());
@@ -56,7 +66,7 @@ impl ProcessingStep3_7 {
 
         // * Output a `__cto1.#ctor_init_name_id(...arguments);` call to `M::new`.
         // * Output a `__cto1` return to `M::new`.
-        let input_args = convert_function_input_to_arguments(&input);
+        let (new_input, input_args) = bind_function_inputs(&input);
         m_new_out.extend::<TokenStream>(quote! {
             __cto1.#ctor_init_name_id(#input_args);
             __cto1
@@ -69,13 +79,21 @@ impl ProcessingStep3_7 {
 
         smtype.method_output().borrow_mut().extend(quote! {
             #(#attr)*
-            #vis fn new #(#type_params)*(arena: &#arena_type_name_id, #input) -> Self #where_clause {
+            #vis fn new #(#type_params)*(arena: &#arena_type_name_id #ty_generics, #new_input) -> Self #where_clause {
                 #m_new_out
             }
         });
+
+        // For deep cloning and deserialization, output a `M::__new_default` method
+        // allocating an instance with default field values, without invoking constructors.
+        smtype.method_output().borrow_mut().extend(quote! {
+            fn __new_default(arena: &#arena_type_name_id #ty_generics) -> Self {
+                #initlayer2
+            }
+        });
     }
 
-    fn init_data(&self, asc_smtype_list: &[Symbol], smtype_index: usize) -> proc_macro2::TokenStream {
+    fn init_data(&self, host: &SModelHost, asc_smtype_list: &[Symbol], smtype_index: usize) -> proc_macro2::TokenStream {
         let smtype = &asc_smtype_list[smtype_index];
         let smtype_name = smtype.name();
         let mut fields = proc_macro2::TokenStream::new();
@@ -97,13 +115,39 @@ impl ProcessingStep3_7 {
         let variant = if smtype_index + 1 < asc_smtype_list.len() {
             let next_m = asc_smtype_list[smtype_index + 1].name();
             let next_m = Ident::new(&(DATA_PREFIX.to_owned() + &next_m), Span::call_site());
-            let i = self.init_data(asc_smtype_list, smtype_index + 1);
-            quote! { #subtype_enum::#next_m(::std::rc::Rc::new(#i)) }
+            let i = self.init_data(host, asc_smtype_list, smtype_index + 1);
+            if host.flat_layout {
+                quote! { #subtype_enum::#next_m(#i) }
+            } else {
+                quote! { #subtype_enum::#next_m(::std::rc::Rc::new(#i)) }
+            }
         } else {
             quote! { #subtype_enum::#data_variant_no_subtype }
         };
         let data_variant_field = Ident::new(DATA_VARIANT_FIELD, Span::call_site());
         let data_id = Ident::new(DATA, Span::call_site());
+
+        // The basemost data structure holds the tag of the
+        // data type being constructed and the object ID, which
+        // is assigned by the arena.
+        if smtype_index == 0 {
+            let tag_enum = tag_enum_name(&asc_smtype_list[0]);
+            let data_tag_field = Ident::new(DATA_TAG_FIELD, Span::call_site());
+            let data_id_field = Ident::new(DATA_ID_FIELD, Span::call_site());
+            let tag = Ident::new(&asc_smtype_list.last().unwrap().name(), Span::call_site());
+            fields.extend(quote! {
+                #data_tag_field: #tag_enum::#tag,
+                #data_id_field: 0,
+            });
+        }
+
+        if host.phantom_type().is_some() {
+            let data_phantom_field = Ident::new(DATA_PHANTOM_FIELD, Span::call_site());
+            fields.extend(quote! {
+                #data_phantom_field: ::std::marker::PhantomData,
+            });
+        }
+
         let smtype_data_name = Ident::new(&format!("{DATA_PREFIX}{}", smtype_name), Span::call_site());
         quote! {
             #data_id::#smtype_data_name {
@@ -3,6 +3,13 @@ use crate::*;
 
 pub const NONDISPATCH_PREFIX: &'static str = "__nd_";
 
+/// Returns the name of the nondispatch method of a method slot, as in
+/// `__nd_m` for `m` and `__nd_fmt_Display_fmt` for `fmt::Display::fmt`.
+pub fn nondispatch_name(slot_name: &str) -> String {
+    let slot_name = slot_name.replace("::", "_").replace(|ch: char| !(ch.is_alphanumeric() || ch == '_'), "_");
+    format!("{NONDISPATCH_PREFIX}{slot_name}")
+}
+
 pub struct ProcessingStep3_8();
 
 impl ProcessingStep3_8 {
@@ -19,8 +26,49 @@ impl ProcessingStep3_8 {
             result_annotation.extend::<proc_macro2::TokenStream>(t.to_token_stream());
         }
 
+        // Validate method name, except at trait implementations,
+        // whose methods do not clash with the generated methods.
+        if node.trait_name.is_none() && RESERVED_METHOD_NAMES.contains(&name.to_string().as_str()) {
+            name.span().unwrap().error(format!("Method name '{}' is reserved for a generated method.", name)).emit();
+            return false;
+        }
+
+        // Validate abstract method
+        if node.is_abstract {
+            if !smtype.is_abstract() {
+                name.span().unwrap().error(format!("Abstract method '{}' in non-abstract data type '{}'.", name, smtype.name())).emit();
+                return false;
+            }
+            if node.is_override {
+                name.span().unwrap().error("Abstract method must not override.").emit();
+                return false;
+            }
+            if node.is_final {
+                name.span().unwrap().error("Abstract method must not be final.").emit();
+                return false;
+            }
+            if Self::begins_with_no_receiver(&node.inputs) {
+                name.span().unwrap().error("Abstract method must have an instance receiver.").emit();
+                return false;
+            }
+        }
+
+        // A trait method takes the visibility of the trait.
+        if node.trait_name.is_some() && !matches!(vis, Visibility::Inherited) {
+            vis.span().unwrap().error("Trait method must not have a visibility.").emit();
+            return false;
+        }
+
         // Static method
         if Self::begins_with_no_receiver(&node.inputs) {
+            if node.is_final {
+                name.span().unwrap().error("Static method must not be final.").emit();
+                return false;
+            }
+            // A static trait method is output to the trait implementation.
+            if node.trait_name.is_some() {
+                return true;
+            }
             let attr = node.attributes.borrow().clone();
             let stmt = &node.statements;
             smtype.method_output().borrow_mut().extend(quote! {
@@ -33,17 +81,30 @@ impl ProcessingStep3_8 {
         }
 
         // Validate receiver
+        if Self::begins_with_mutable_reference_receiver(&node.inputs) {
+            node.inputs.first().span().unwrap().error("Instance receiver must not be `&mut self`: objects are shared handles, therefore fields are mutated through `&self` using their setters.").emit();
+            return false;
+        }
         if !Self::begins_with_instance_receiver(&node.inputs) {
-            node.inputs.span().unwrap().error("Instance receiver must be exactly `&self`.").emit();
+            node.inputs.first().span().unwrap().error("Instance receiver must be either `&self` or `self`.").emit();
             return false;
         }
+        let is_by_value = Self::begins_with_by_value_receiver(&node.inputs);
 
         // Remove the receiver
         let mut inputs1 = node.inputs.iter().cloned().collect::<Vec<_>>();
-        inputs1.remove(0);
+        let receiver = inputs1.remove(0);
         let mut inputs = Punctuated::<FnArg, Comma>::new();
         inputs.extend(inputs1);
 
+        let signature = MethodSignature {
+            visibility: node.visibility.clone(),
+            generics: node.generics.clone(),
+            is_by_value,
+            parameter_types: inputs.iter().filter_map(|arg| if let FnArg::Typed(pt) = arg { Some(pt.ty.as_ref().clone()) } else { None }).collect(),
+            result_type: node.result_type.clone(),
+        };
+
         // * Look for the #[doc] attribute.
         // * Look for the #[inheritdoc] attribute.
         let mut doc_attr: Vec<syn::Attribute> = vec![];
@@ -66,8 +127,13 @@ impl ProcessingStep3_8 {
             i += 1;
         }
 
-        // Create a `MethodSlot` with the appropriate settings.
-        let slot = host.factory.create_method_slot(name.to_string(), smtype.clone(), doc_attr);
+        // Create a `MethodSlot` with the appropriate settings. The slot
+        // of a trait method is named after the trait path, as in `fmt::Display::m`.
+        let slot_name = match &node.trait_name {
+            Some(trait_name) => format!("{trait_name}::{name}"),
+            None => name.to_string(),
+        };
+        let slot = host.factory.create_method_slot(slot_name, smtype.clone(), doc_attr, node.is_abstract, node.is_final, signature);
 
         // Map node to slot
         host.semantics.set(&node, Some(slot.clone()));
@@ -79,6 +145,30 @@ impl ProcessingStep3_8 {
         }
         smtype.methods().set(slot.name(), slot.clone());
 
+        // A method not marked `override` must either not have the name of
+        // a base method, or be marked `new`, hiding a non-final base method.
+        if !node.is_override {
+            let base_method = smtype.lookup_method_in_base_smtype(&slot.name());
+            if node.is_new {
+                let Some(base_method) = base_method else {
+                    name.span().unwrap().error(format!("No method '{}' in base.", slot.name())).emit();
+                    return false;
+                };
+                if base_method.is_final() {
+                    name.span().unwrap().error(format!("Method '{}' is final in '{}'.", name, base_method.defined_in().name())).emit();
+                    return false;
+                }
+            } else if let Some(base_method) = base_method {
+                let message = format!("Method '{}' hides the method of '{}'; mark it `override` to override it, or `new` to hide it.", slot.name(), base_method.defined_in().name());
+                if host.warn_missing_override {
+                    name.span().unwrap().warning(message).emit();
+                } else {
+                    name.span().unwrap().error(message).emit();
+                    return false;
+                }
+            }
+        }
+
         // Check if the method has a `#[inheritdoc]` attribute; if it has one:
         //
         // * Remove it
@@ -98,23 +188,25 @@ impl ProcessingStep3_8 {
         }
 
         // Define `nondispatch_name` as nondispatch prefix plus method name.
-        let nondispatch_name = format!("{NONDISPATCH_PREFIX}{}", slot.name());
-        let nondispatch_name_id = Ident::new(&nondispatch_name, name.span());
-
-        // Define input argument list
-        let input_args = convert_function_input_to_arguments(&inputs);
+        let nondispatch_name_id = Ident::new(&nondispatch_name(&slot.name()), name.span());
 
-        // Process super expressions
-        let statements = self.process_super_expression(node.statements.clone(), smtype, &slot);
+        // Process super expressions. The nondispatch method of an abstract
+        // method is unreachable as every non-abstract data type overrides it.
+        let statements = if node.is_abstract {
+            quote! { unreachable!() }
+        } else {
+            self.process_super_expression(node.statements.clone(), smtype, &slot)
+        };
 
         // If the method is marked as "override"
         //
         // * Lookup for a method with the same name in one of the base data types
-        // * Contribute "overriding" return call code to the respective
-        //   override logic mapping according to smtype inheritance.
+        // * Record that the method slot overrides the base method slot.
         if node.is_override {
             if let Some(base_method) = smtype.lookup_method_in_base_smtype(&slot.name()) {
-                self.perform_override(&slot.name(), base_method.override_logic_mapping(), &base_method.defined_in(), smtype, &input_args);
+                if !self.perform_override(host, node, &slot, &base_method) {
+                    return false;
+                }
             } else {
                 name.span().unwrap().error(format!("No method '{}' in base.", slot.name())).emit();
             }
@@ -142,9 +234,16 @@ impl ProcessingStep3_8 {
             attr.remove(*i);
         }
 
+        if node.is_abstract {
+            attr.push(syn::parse_quote! { #[allow(unused_variables)] });
+        }
+        if node.trait_name.is_some() {
+            attr.push(syn::parse_quote! { #[allow(non_snake_case)] });
+        }
+
         smtype.method_output().borrow_mut().extend(quote! {
             #(#attr)*
-            fn #nondispatch_name_id #(#type_params)*(&self, #inputs) #result_annotation #where_clause {
+            fn #nondispatch_name_id #(#type_params)*(#receiver, #inputs) #result_annotation #where_clause {
                 #statements
             }
         });
@@ -160,21 +259,18 @@ impl ProcessingStep3_8 {
         }
     }
 
-    // Checks whether method formally begins with the exact `&self` receiver.
+    // Checks whether method formally begins with the exact `&self` receiver,
+    // or with a `self` or `mut self` receiver.
     fn begins_with_instance_receiver(input: &Punctuated<FnArg, Comma>) -> bool {
         if let Some(first) = input.first() {
             if let FnArg::Receiver(rec) = first {
-                if !rec.attrs.is_empty() || rec.mutability.is_some() {
+                if !rec.attrs.is_empty() || rec.colon_token.is_some() {
                     return false;
                 }
                 let Some(reference) = rec.reference.as_ref() else {
-                    return false
+                    return true;
                 };
-                if reference.1.is_some() {
-                    return false;
-                }
-                // Ignore the type for now, assuming Self.
-                true
+                reference.1.is_none() && rec.mutability.is_none()
             } else {
                 false
             }
@@ -183,6 +279,16 @@ impl ProcessingStep3_8 {
         }
     }
 
+    // Checks whether method formally begins with a `self` or `mut self` receiver.
+    pub fn begins_with_by_value_receiver(input: &Punctuated<FnArg, Comma>) -> bool {
+        matches!(input.first(), Some(FnArg::Receiver(rec)) if rec.reference.is_none() && rec.colon_token.is_none())
+    }
+
+    // Checks whether method formally begins with a `&mut self` receiver.
+    fn begins_with_mutable_reference_receiver(input: &Punctuated<FnArg, Comma>) -> bool {
+        matches!(input.first(), Some(FnArg::Receiver(rec)) if rec.reference.is_some() && rec.mutability.is_some())
+    }
+
     fn process_super_expression(&self, input: proc_macro2::TokenStream, smtype: &Symbol, method_slot: &Symbol) -> proc_macro2::TokenStream {
         let mut input = input.into_iter();
         let mut output = proc_macro2::TokenStream::new();
@@ -241,11 +347,19 @@ impl ProcessingStep3_8 {
 
                     // Found super expression.
 
-                    // Lookup for a method in one of the base data types.
-                    let Some(base_method) = smtype.lookup_method_in_base_smtype(&id.to_string()) else {
+                    // Lookup for a method in one of the base data types. In a trait
+                    // method, lookup for a method of the same trait first.
+                    let trait_method = method_slot.name().rsplit_once("::").map(|(trait_name, _)| format!("{trait_name}::{id}"));
+                    let base_method = trait_method.and_then(|name| smtype.lookup_method_in_base_smtype(&name))
+                        .or_else(|| smtype.lookup_method_in_base_smtype(&id.to_string()));
+                    let Some(base_method) = base_method else {
                         id.span().unwrap().error(format!("No method '{}' in base.", id.to_string())).emit();
                         continue;
                     };
+                    if base_method.is_abstract() {
+                        id.span().unwrap().error(format!("Method '{}' is abstract in '{}'.", id.to_string(), base_method.defined_in().name())).emit();
+                        continue;
+                    }
 
                     // Let base be "self" followed by n = delta_of_descending_list_until_base_type
                     // (where `base_type` is the base found method's `.defined_in()` call)
@@ -261,13 +375,15 @@ impl ProcessingStep3_8 {
                     }
                     let base = proc_macro2::TokenStream::from_str(&base).unwrap();
 
-                    // Replace super.m(...) by BaseM::#nondispatch_name_id(&#base, ...)
-                    let nondispatch_name = format!("{NONDISPATCH_PREFIX}{}", base_method.name());
-                    let nondispatch_name_id = Ident::new(&nondispatch_name, Span::call_site());
+                    // Replace super.m(...) by BaseM::#nondispatch_name_id(&#base, ...),
+                    // or by BaseM::#nondispatch_name_id(#base.clone(), ...) if the base
+                    // method takes `self` by value.
+                    let nondispatch_name_id = Ident::new(&nondispatch_name(&base_method.name()), Span::call_site());
                     let base_smtype = Ident::new(&base_method.defined_in().name(), Span::call_site());
                     let super_args = self.process_super_expression(g.stream(), smtype, method_slot);
+                    let base = if base_method.is_by_value() { quote! { #base.clone() } } else { quote! { &#base } };
                     output.extend(quote! {
-                        #base_smtype::#nondispatch_name_id(&#base, #super_args)
+                        #base_smtype::#nondispatch_name_id(#base, #super_args)
                     });
                 },
                 proc_macro2::TokenTree::Group(g) => {
@@ -282,41 +398,120 @@ impl ProcessingStep3_8 {
         output
     }
 
-    fn perform_override(&self, method_name: &str, mut override_logic_mapping: SharedMap<Symbol, Rc<OverrideLogicMapping>>, base_smtype: &Symbol, target_smtype: &Symbol, input_args: &Punctuated<proc_macro2::TokenStream, Comma>) {
-        let smtype_list = target_smtype.asc_smtype_list();
-        let mut i = 0usize;
-        for m in smtype_list.iter() {
-            if m == base_smtype {
-                break;
-            }
-            i += 1;
+    fn perform_override(&self, host: &SModelHost, node: &SmTypeMethod, slot: &Symbol, base_method: &Symbol) -> bool {
+        let method_name = &node.name;
+
+        // A final method may not be overriden.
+        if base_method.is_final() {
+            method_name.span().unwrap().error(format!("Method '{}' is final in '{}'.", method_name, base_method.defined_in().name())).emit();
+            return false;
+        }
+
+        // The signature must be compatible with the overriden method.
+        if !self.check_override_signature(host, node, slot, base_method) {
+            return false;
+        }
+
+        // Record the overriden method; the dynamic dispatch
+        // logic is generated afterwards.
+        slot.set_overrides(Some(base_method));
+        true
+    }
+
+    // Compares the signature of an overriding method against the overriden
+    // method, reporting the first difference at the overriding signature.
+    // `Self` within a parameter or result type stands for the declaring
+    // data type, except for a result type of exactly `Self`, which
+    // is converted on dispatch.
+    fn check_override_signature(&self, host: &SModelHost, node: &SmTypeMethod, slot: &Symbol, base_method: &Symbol) -> bool {
+        let signature = slot.signature();
+        let base_signature = base_method.signature();
+        let base_name = base_method.defined_in().name();
+        let (_, ty_generics, _) = host.split_generics();
+        let self_type = Ident::new(&slot.defined_in().name(), Span::call_site());
+        let self_type = quote! { #self_type #ty_generics };
+        let base_self_type = Ident::new(&base_name, Span::call_site());
+        let base_self_type = quote! { #base_self_type #ty_generics };
+
+        // Visibility
+        let visibility = tokens_to_string(&signature.visibility);
+        let base_visibility = tokens_to_string(&base_signature.visibility);
+        if visibility != base_visibility {
+            let span = if visibility.is_empty() { node.name.span() } else { node.visibility.span() };
+            let expected = if base_visibility.is_empty() { "private".to_owned() } else { format!("'{base_visibility}'") };
+            span.unwrap().error(format!("Visibility must be {expected}, as in '{base_name}'.")).emit();
+            return false;
         }
-        for m in smtype_list[(i + 1)..(smtype_list.len() - 1)].iter() {
-            if let Some(old_mapping) = override_logic_mapping.get(m) {
-                override_logic_mapping = old_mapping.override_logic_mapping();
+
+        // Generic parameters
+        let generics_to_string = |generics: &Generics| {
+            let where_clause = &generics.where_clause;
+            tokens_to_string(&quote! { #generics #where_clause })
+        };
+        let generics = generics_to_string(&signature.generics);
+        let base_generics = generics_to_string(&base_signature.generics);
+        if generics != base_generics {
+            let span = if generics.is_empty() { node.name.span() } else { node.generics.span() };
+            if base_generics.is_empty() {
+                span.unwrap().error(format!("Method must have no generic parameters, as in '{base_name}'.")).emit();
             } else {
-                let new_mapping = Rc::new(OverrideLogicMapping::new());
-                override_logic_mapping.set(m.clone(), new_mapping.clone());
-                override_logic_mapping = new_mapping.override_logic_mapping();
+                span.unwrap().error(format!("Generic parameters must be '{base_generics}', as in '{base_name}'.")).emit();
+            }
+            return false;
+        }
+
+        // Receiver
+        if signature.is_by_value != base_signature.is_by_value {
+            let expected = if base_signature.is_by_value { "self" } else { "&self" };
+            node.inputs.first().span().unwrap().error(format!("Receiver must be `{expected}`, as in '{base_name}'.")).emit();
+            return false;
+        }
+
+        // Parameters
+        let parameter_types = node.inputs.iter().skip(1).filter_map(|arg| if let FnArg::Typed(pt) = arg { Some(pt.ty.clone()) } else { None }).collect::<Vec<_>>();
+        if parameter_types.len() != base_signature.parameter_types.len() {
+            let n = base_signature.parameter_types.len();
+            node.inputs.span().unwrap().error(format!("Method must have {n} parameter{}, as in '{base_name}'.", if n == 1 { "" } else { "s" })).emit();
+            return false;
+        }
+        for (t, base_t) in parameter_types.iter().zip(base_signature.parameter_types.iter()) {
+            let base_t = tokens_to_string(&Self::replace_self(base_t.to_token_stream(), &base_self_type));
+            if tokens_to_string(&Self::replace_self(t.to_token_stream(), &self_type)) != base_t {
+                t.span().unwrap().error(format!("Parameter type must be '{base_t}', as in '{base_name}'.")).emit();
+                return false;
             }
         }
 
-        // Generate layers
-        let mut layers = String::new();
-        let mut parens = 0usize;
-        for m in smtype_list[(i + 1)..].iter().rev() {
-            layers.push_str(&format!("{}(", m.name()));
-            parens += 1;
+        // Result type
+        let result_type_to_string = |t: &Option<Type>, self_type: &proc_macro2::TokenStream| {
+            let Some(t) = t else {
+                return "()".to_owned();
+            };
+            if matches!(t, Type::Path(p) if p.qself.is_none() && p.path.is_ident("Self")) {
+                return "Self".to_owned();
+            }
+            tokens_to_string(&Self::replace_self(t.to_token_stream(), self_type))
+        };
+        let result_type = result_type_to_string(&signature.result_type, &self_type);
+        let base_result_type = result_type_to_string(&base_signature.result_type, &base_self_type);
+        if result_type != base_result_type {
+            let span = node.result_type.as_ref().map(|t| t.span()).unwrap_or(node.name.span());
+            span.unwrap().error(format!("Return type must be '{base_result_type}', as in '{base_name}'.")).emit();
+            return false;
         }
-        layers.push_str("self.clone()");
-        layers.push_str(&")".repeat(parens));
-
-        let new_mapping = Rc::new(OverrideLogicMapping::new());
-        let layers = proc_macro2::TokenStream::from_str(&layers).unwrap();
-        let method_name_id = Ident::new(&method_name, Span::call_site());
-        new_mapping.set_override_code(Some(quote! {
-            return #layers.#method_name_id(#input_args);
-        }));
-        override_logic_mapping.set(target_smtype.clone(), new_mapping.clone());
+
+        true
+    }
+
+    // Replaces `Self` within tokens by a given type.
+    fn replace_self(tokens: proc_macro2::TokenStream, self_type: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        tokens.into_iter().map(|token| match token {
+            proc_macro2::TokenTree::Ident(id) if id == "Self" => self_type.clone(),
+            proc_macro2::TokenTree::Group(g) => {
+                let stream = Self::replace_self(g.stream(), self_type);
+                proc_macro2::TokenTree::Group(proc_macro2::Group::new(g.delimiter(), stream)).into()
+            },
+            token => token.into(),
+        }).collect()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,64 @@
+use crate::*;
+
+pub struct ProcessingStep3_9();
+
+impl ProcessingStep3_9 {
+    // Ensure a non-abstract data type overrides every abstract method,
+    // and that a data type implements the methods of the interfaces it lists.
+    pub fn exec(&self, host: &mut SModelHost, node: &Rc<SmType>, smtype: &Symbol) -> bool {
+        let mut valid = self.check_interfaces(host, node, smtype);
+
+        if smtype.is_abstract() {
+            return valid;
+        }
+
+        // For each abstract method from the basemost data type
+        // to this data type, lookup the most specific method
+        // of the same name; if it is still abstract, report an error.
+        for m in smtype.asc_smtype_list().iter() {
+            for (name, method) in m.methods().borrow().iter() {
+                if !method.is_abstract() {
+                    continue;
+                }
+                if smtype.lookup_method(name).map(|mt| mt.is_abstract()).unwrap_or(false) {
+                    node.name.span().unwrap().error(format!("Data type '{}' must override abstract method '{}' from '{}'.", smtype.name(), name, m.name())).emit();
+                    valid = false;
+                }
+            }
+        }
+
+        valid
+    }
+
+    // For each method of each interface the data type lists, lookup the
+    // most specific method of the same name; report an error if there
+    // is none or if its signature differs from the interface method.
+    fn check_interfaces(&self, host: &SModelHost, node: &Rc<SmType>, smtype: &Symbol) -> bool {
+        let mut valid = true;
+        for interface_id in node.interfaces.iter() {
+            let interface = host.interfaces.iter().find(|i| &i.name == interface_id).unwrap();
+            for im in interface.methods.iter() {
+                let Some(method) = smtype.lookup_method(&im.name.to_string()) else {
+                    interface_id.span().unwrap().error(format!("Data type '{}' must implement method '{}' of interface '{}'.", smtype.name(), im.name, interface.name)).emit();
+                    valid = false;
+                    continue;
+                };
+                let signature = method.signature();
+                let generics_to_string = |generics: &Generics| {
+                    let where_clause = &generics.where_clause;
+                    tokens_to_string(&quote! { #generics #where_clause })
+                };
+                let parameter_types = im.inputs.iter().skip(1).filter_map(|arg| if let FnArg::Typed(pt) = arg { Some(tokens_to_string(&pt.ty)) } else { None }).collect::<Vec<_>>();
+                let compatible = signature.is_by_value == ProcessingStep3_8::begins_with_by_value_receiver(&im.inputs)
+                    && generics_to_string(&signature.generics) == generics_to_string(&im.generics)
+                    && signature.parameter_types.iter().map(tokens_to_string).collect::<Vec<_>>() == parameter_types
+                    && signature.result_type.as_ref().map(tokens_to_string).unwrap_or("()".into()) == im.result_type.as_ref().map(tokens_to_string).unwrap_or("()".into());
+                if !compatible {
+                    interface_id.span().unwrap().error(format!("Method '{}' of '{}' must have the signature of interface '{}'.", im.name, method.defined_in().name(), interface.name)).emit();
+                    valid = false;
+                }
+            }
+        }
+        valid
+    }
+}
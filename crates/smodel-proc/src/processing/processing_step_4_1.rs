@@ -28,49 +28,104 @@ impl ProcessingStep4_1 {
         inputs.extend(inputs1);
 
         // Define `nondispatch_name` as nondispatch prefix plus method name.
-        let nondispatch_name = format!("{NONDISPATCH_PREFIX}{}", slot.name());
-        let nondispatch_name_id = Ident::new(&nondispatch_name, Span::call_site());
+        let nondispatch_name_id = Ident::new(&nondispatch_name(&slot.name()), Span::call_site());
 
-        // Define input argument list
-        let input_args = convert_function_input_to_arguments(&inputs);
+        // Bind the parameters positionally, defining the input argument list.
+        let (inputs, input_args) = bind_function_inputs(&inputs);
 
         // Contribute the method #method_name with prepended dynamic dispatch logic,
         // invoking `self.#nondispatch_name(#input_args)` at the end of the method body,
-        // to the `impl` output.
-        let dynamic_dispatch = self.generate_dynamic_dispatch(slot.override_logic_mapping());
+        // to the `impl` output. A final method is never overriden, therefore
+        // it has no dynamic dispatch logic.
+        let dynamic_dispatch = if slot.is_final() {
+            proc_macro2::TokenStream::new()
+        } else {
+            self.generate_dynamic_dispatch(&slot, smtype, &input_args, node.result_type.as_ref())
+        };
+        let receiver = if slot.is_by_value() { quote! { self } } else { quote! { &self } };
 
         smtype.method_output().borrow_mut().extend(quote! {
             #(#attr)*
-            #vis fn #name #(#type_params)*(&self, #inputs) #result_annotation #where_clause {
+            #vis fn #name #(#type_params)*(#receiver, #inputs) #result_annotation #where_clause {
                 #dynamic_dispatch
                 self.#nondispatch_name_id(#input_args)
             }
         });
     }
 
-    fn generate_dynamic_dispatch(&self, mapping: SharedMap<Symbol, Rc<OverrideLogicMapping>>) -> proc_macro2::TokenStream {
-        let mut out = proc_macro2::TokenStream::new();
-        let mut first = true;
-        for (subtype, logic) in mapping.borrow().iter() {
-            let subtype_name = Ident::new(&subtype.name(), Span::call_site());
-            if !first {
-                out.extend(quote! { else });
+    /// Generates a single `match` on the dynamic data type tag, invoking
+    /// the most specific override of the method for each non-abstract
+    /// subtype that overrides it. A `Self` result of an override
+    /// is converted into this data type.
+    pub fn generate_dynamic_dispatch(&self, slot: &Symbol, smtype: &Symbol, input_args: &Punctuated<proc_macro2::TokenStream, Comma>, result_type: Option<&Type>) -> proc_macro2::TokenStream {
+        // Group the non-abstract subtypes by the method slot
+        // that overrides the method for them.
+        let mut overrides: Vec<(Symbol, Vec<Symbol>)> = vec![];
+        for subtype in smtype.descendant_smtype_list().iter() {
+            if subtype.is_abstract() {
+                continue;
             }
-            let mut d1 = self.generate_dynamic_dispatch(logic.override_logic_mapping());
-            if d1.is_empty() {
-                if let Some(code) = logic.override_code() {
-                    d1.extend(code);
-                }
-            } else if let Some(code) = logic.override_code() {
-                d1.extend(quote! { else { #code } });
+            let Some(override_slot) = self.lookup_override(slot, smtype, subtype) else {
+                continue;
+            };
+            if let Some((_, tags)) = overrides.iter_mut().find(|(m, _)| m == &override_slot) {
+                tags.push(subtype.clone());
+            } else {
+                overrides.push((override_slot, vec![subtype.clone()]));
             }
-            out.extend(quote! {
-                if self.is::<#subtype_name>() {
-                    #d1
-                }
+        }
+
+        if overrides.is_empty() {
+            return proc_macro2::TokenStream::new();
+        }
+
+        let tag_enum = tag_enum_name(smtype);
+        let data_tag_field = Ident::new(DATA_TAG_FIELD, Span::call_site());
+        let nondispatch_name_id = Ident::new(&nondispatch_name(&slot.name()), Span::call_site());
+        let returns_self = matches!(result_type, Some(Type::Path(p)) if p.qself.is_none() && p.path.is_ident("Self"));
+        let conversion = if returns_self { quote! { .into() } } else { proc_macro2::TokenStream::new() };
+        let mut arms = proc_macro2::TokenStream::new();
+        for (override_slot, tags) in overrides.iter() {
+            let tags = tags.iter().map(|m| Ident::new(&m.name(), Span::call_site()));
+
+            // Generate `MN(...M1(self.clone()))` layers from the subtype
+            // after this data type to the data type defining the override.
+            let defined_in = override_slot.defined_in();
+            let smtype_list = defined_in.asc_smtype_list();
+            let i = smtype_list.iter().position(|m| m == smtype).unwrap();
+            let layers = proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root("self", &smtype_list[(i + 1)..])).unwrap();
+            let defined_in_id = Ident::new(&defined_in.name(), Span::call_site());
+            let layers = if slot.is_by_value() { layers } else { quote! { &#layers } };
+
+            arms.extend(quote! {
+                #(#tag_enum::#tags)|* => {
+                    return #defined_in_id::#nondispatch_name_id(#layers, #input_args) #conversion;
+                },
             });
-            first = false;
         }
-        out
+
+        quote! {
+            match self.#data_tag_field() {
+                #arms
+                #[allow(unreachable_patterns)]
+                _ => {},
+            }
+        }
+    }
+
+    /// Looks up the most specific method slot in `subtype`, not counting
+    /// `smtype`, that overrides `slot` directly or indirectly.
+    fn lookup_override(&self, slot: &Symbol, smtype: &Symbol, subtype: &Symbol) -> Option<Symbol> {
+        for m in subtype.asc_smtype_list().iter().rev() {
+            if m == smtype {
+                break;
+            }
+            if let Some(m_slot) = m.methods().get(&slot.name()) {
+                if m_slot.overrides_transitively(slot) {
+                    return Some(m_slot);
+                }
+            }
+        }
+        None
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,83 @@
+use crate::*;
+
+pub struct ProcessingStep4_2();
+
+impl ProcessingStep4_2 {
+    // Implement a Debug writing the fields of the data type an
+    // object was constructed as, from the basemost data type.
+    pub fn exec(&self, host: &mut SModelHost, smtype: &Symbol, smodel_path: &proc_macro2::TokenStream) {
+        let smtype_name = Ident::new(&smtype.name(), Span::call_site());
+        let smtype_name_str = smtype.name();
+        let tag_enum = tag_enum_name(smtype);
+
+        // The root `Weak<#DATA::FirstM>` value, identifying the object
+        // for cycle protection.
+        let mut base_accessor = "self.0".to_owned();
+        let mut m = smtype.clone();
+        while let Some(m1) = m.inherits() {
+            base_accessor.push_str(".0");
+            m = m1;
+        }
+        let base_accessor = proc_macro2::TokenStream::from_str(&base_accessor).unwrap();
+
+        // For each non-abstract data type an object may be constructed as,
+        // convert to that data type and write its fields.
+        let mut arms = proc_macro2::TokenStream::new();
+        for subtype in [smtype.clone()].into_iter().chain(smtype.descendant_smtype_list()) {
+            if subtype.is_abstract() {
+                continue;
+            }
+            let subtype_name = Ident::new(&subtype.name(), Span::call_site());
+            let subtype_name_str = subtype.name();
+
+            // Generate `MN(...M1(self.clone()))` layers from the data type
+            // after this data type to the subtype.
+            let asc_smtype_list = subtype.asc_smtype_list();
+            let i = asc_smtype_list.iter().position(|m| m == smtype).unwrap();
+            let conversion = proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root("self", &asc_smtype_list[(i + 1)..])).unwrap();
+
+            let mut fields = proc_macro2::TokenStream::new();
+            for m in asc_smtype_list.iter() {
+                let m_name = Ident::new(&m.name(), Span::call_site());
+                for (field_name, _) in m.fields().borrow().iter() {
+                    let field_name_id = Ident::new(field_name, Span::call_site());
+                    fields.extend(quote! {
+                        .field(#field_name, &#m_name::#field_name_id(&o))
+                    });
+                }
+            }
+            arms.extend(quote! {
+                #tag_enum::#subtype_name => {
+                    let o = #conversion;
+                    f.debug_struct(#subtype_name_str) #fields .finish()
+                },
+            });
+        }
+
+        // Like `#[derive(Debug)]`, require Debug on the type parameters.
+        let predicates = host.generics.type_params().map(|p| {
+            let id = &p.ident;
+            quote! { #id: ::std::fmt::Debug }
+        }).collect::<Vec<_>>();
+        let (impl_generics, ty_generics, _) = host.split_generics();
+        let where_clause = host.where_clause_with(&predicates);
+
+        host.output.extend::<TokenStream>(quote! {
+            impl #impl_generics ::std::fmt::Debug for #smtype_name #ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    if !self.is_alive() {
+                        return write!(f, "{}()", #smtype_name_str);
+                    }
+                    let written = #smodel_path::debug_guard(#base_accessor.as_ptr() as *const (), || {
+                        match self.kind() {
+                            #arms
+                            #[allow(unreachable_patterns)]
+                            _ => unreachable!(),
+                        }
+                    });
+                    written.unwrap_or_else(|| f.debug_struct(self.type_name()).finish_non_exhaustive())
+                }
+            }
+        }.try_into().unwrap());
+    }
+}
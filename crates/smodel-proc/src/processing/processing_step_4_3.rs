@@ -0,0 +1,162 @@
+use crate::*;
+
+pub struct ProcessingStep4_3();
+
+impl ProcessingStep4_3 {
+    // Implement serialization of a data type, serializing
+    // an instance as its object ID within the arena being serialized.
+    pub fn exec(&self, host: &mut SModelHost, smtype: &Symbol, smodel_path: &proc_macro2::TokenStream) {
+        let smtype_name = Ident::new(&smtype.name(), Span::call_site());
+        let basemost = smtype.asc_smtype_list()[0].clone();
+        let basemost_name = Ident::new(&basemost.name(), Span::call_site());
+        let basemost_data_name = Ident::new(&format!("{DATA_PREFIX}{}", basemost.name()), Span::call_site());
+        let data_id = Ident::new(DATA, Span::call_site());
+        let serde = quote! { #smodel_path::serde };
+
+        let conversion = if smtype == &basemost {
+            quote! { Ok(root) }
+        } else {
+            quote! { #smtype_name::try_from(root).map_err(#serde::de::Error::custom) }
+        };
+
+        // The root `Weak<#DATA::FirstM>` value.
+        let mut base_accessor = "self.0".to_owned();
+        for _ in 1..smtype.asc_smtype_list().len() {
+            base_accessor.push_str(".0");
+        }
+        let base_accessor = proc_macro2::TokenStream::from_str(&base_accessor).unwrap();
+
+        host.output.extend::<TokenStream>(quote! {
+            impl #serde::Serialize for #smtype_name {
+                fn serialize<S: #serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    #smodel_path::serialize_id(&#base_accessor, self.id(), serializer)
+                }
+            }
+
+            impl<'de> #serde::Deserialize<'de> for #smtype_name {
+                fn deserialize<D: #serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let id = <usize as #serde::Deserialize>::deserialize(deserializer)?;
+                    let data = #smodel_path::resolve_id::<#data_id::#basemost_data_name, D::Error>(id)?;
+                    let root = <#data_id::#basemost_data_name as #smodel_path::ArenaData<#basemost_name>>::handle(&data);
+                    #conversion
+                }
+            }
+        }.try_into().unwrap());
+
+        if smtype == &basemost {
+            self.serialize_arena(host, smtype, smodel_path);
+        }
+    }
+
+    // Implement `ArenaSerde` for the basemost data structure,
+    // serializing the fields of each non-abstract data type as a tuple.
+    fn serialize_arena(&self, host: &mut SModelHost, smtype: &Symbol, smodel_path: &proc_macro2::TokenStream) {
+        let smtype_name = Ident::new(&smtype.name(), Span::call_site());
+        let smtype_data_name = Ident::new(&format!("{DATA_PREFIX}{}", smtype.name()), Span::call_site());
+        let data_id = Ident::new(DATA, Span::call_site());
+        let data_tag_field = Ident::new(DATA_TAG_FIELD, Span::call_site());
+        let tag_enum = tag_enum_name(smtype);
+        let serde = quote! { #smodel_path::serde };
+
+        let mut allocate_arms = proc_macro2::TokenStream::new();
+        let mut serialize_arms = proc_macro2::TokenStream::new();
+        let mut deserialize_arms = proc_macro2::TokenStream::new();
+
+        for subtype in [smtype.clone()].into_iter().chain(smtype.descendant_smtype_list()) {
+            if subtype.is_abstract() {
+                continue;
+            }
+            let subtype_name = Ident::new(&subtype.name(), Span::call_site());
+            let subtype_name_str = subtype.name();
+
+            // Generate `MN(...M1(__root.clone()))` layers from the data type
+            // after the basemost data type to the subtype.
+            let conversion = proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root("__root", &subtype.asc_smtype_list()[1..])).unwrap();
+
+            // Fields from the basemost data type.
+            let mut getters = vec![];
+            let mut setters = vec![];
+            for m in subtype.asc_smtype_list().iter() {
+                let m_name = Ident::new(&m.name(), Span::call_site());
+                for (field_name, _) in m.fields().borrow().iter() {
+                    let getter = Ident::new(field_name, Span::call_site());
+                    let setter = Ident::new(&format!("set_{field_name}"), Span::call_site());
+                    getters.push(quote! { #m_name::#getter });
+                    setters.push(quote! { #m_name::#setter });
+                }
+            }
+            let field_count = getters.len();
+            let indices = 0..field_count;
+            let expecting = format!("the fields of '{subtype_name_str}'");
+
+            allocate_arms.extend(quote! {
+                #subtype_name_str => {
+                    #subtype_name::__new_default(arena);
+                    true
+                },
+            });
+
+            serialize_arms.extend(quote! {
+                #tag_enum::#subtype_name => {
+                    let o = #conversion;
+                    let mut t = serializer.serialize_tuple(#field_count)?;
+                    #(t.serialize_element(&#getters(&o))?;)*
+                    t.end()
+                },
+            });
+
+            deserialize_arms.extend(quote! {
+                #tag_enum::#subtype_name => {
+                    struct __Fields(#subtype_name);
+
+                    impl<'de> #serde::de::Visitor<'de> for __Fields {
+                        type Value = ();
+
+                        fn expecting(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                            f.write_str(#expecting)
+                        }
+
+                        #[allow(unused_mut)]
+                        fn visit_seq<A: #serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+                            #(#setters(&self.0, seq.next_element()?.ok_or_else(|| #serde::de::Error::invalid_length(#indices, &self))?);)*
+                            Ok(())
+                        }
+                    }
+
+                    deserializer.deserialize_tuple(#field_count, __Fields(#conversion))
+                },
+            });
+        }
+
+        host.output.extend::<TokenStream>(quote! {
+            impl #smodel_path::ArenaSerde for #data_id::#smtype_data_name {
+                fn type_name(data: &::std::rc::Rc<Self>) -> &'static str {
+                    data.#data_tag_field.name()
+                }
+
+                fn allocate_default(arena: &#smodel_path::Arena<Self>, type_name: &str) -> bool {
+                    match type_name {
+                        #allocate_arms
+                        _ => false,
+                    }
+                }
+
+                #[allow(unused_imports)]
+                fn serialize_fields<S: #serde::Serializer>(data: &::std::rc::Rc<Self>, serializer: S) -> Result<S::Ok, S::Error> {
+                    use #serde::ser::SerializeTuple;
+                    let __root = <Self as #smodel_path::ArenaData<#smtype_name>>::handle(data);
+                    match __root.kind() {
+                        #serialize_arms
+                    }
+                }
+
+                fn deserialize_fields<'de, D: #serde::Deserializer<'de>>(data: &::std::rc::Rc<Self>, deserializer: D) -> Result<(), D::Error> {
+                    let __root = <Self as #smodel_path::ArenaData<#smtype_name>>::handle(data);
+                    match __root.kind() {
+                        #deserialize_arms
+                    }
+                }
+            }
+        }.try_into().unwrap());
+    }
+}
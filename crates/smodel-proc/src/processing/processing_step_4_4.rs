@@ -0,0 +1,122 @@
+use crate::*;
+
+pub struct ProcessingStep4_4();
+
+impl ProcessingStep4_4 {
+    // Implement deep cloning of a data type into an arena.
+    pub fn exec(&self, host: &mut SModelHost, smtype: &Symbol, arena_type_name: &str, smodel_path: &proc_macro2::TokenStream) {
+        let smtype_name = Ident::new(&smtype.name(), Span::call_site());
+        let basemost = smtype.asc_smtype_list()[0].clone();
+        let basemost_name = Ident::new(&basemost.name(), Span::call_site());
+        let arena_type_name_id = Ident::new(arena_type_name, Span::call_site());
+
+        // The basemost data type layer of `self`.
+        let mut basemost_accessor = "self".to_owned();
+        for _ in 1..smtype.asc_smtype_list().len() {
+            basemost_accessor.push_str(".0");
+        }
+        let basemost_accessor = proc_macro2::TokenStream::from_str(&basemost_accessor).unwrap();
+
+        // Generate `MN(...M2(copy.clone()))` layers over the basemost copy.
+        let conversion = if smtype == &basemost {
+            quote! { copy }
+        } else {
+            proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root("copy", &smtype.asc_smtype_list()[1..])).unwrap()
+        };
+
+        let (impl_generics, ty_generics, _) = host.split_generics();
+        let where_clause = host.where_clause_with(&[quote! { Self: 'static }]);
+
+        host.output.extend::<TokenStream>(quote! {
+            impl #impl_generics #smodel_path::DeepClone for #smtype_name #ty_generics #where_clause {
+                fn deep_clone(&self, cx: &mut #smodel_path::DeepCloneContext) -> Self {
+                    let copy = #basemost_name::__deep_clone(&#basemost_accessor, cx);
+                    #conversion
+                }
+            }
+        }.try_into().unwrap());
+
+        smtype.method_output().borrow_mut().extend(quote! {
+            /// Copies the object into an arena, preserving the data type the object
+            /// was constructed as, along with the objects reachable from its fields.
+            pub fn deep_clone_into(&self, arena: &#arena_type_name_id #ty_generics) -> Self where Self: 'static {
+                #smodel_path::DeepClone::deep_clone(self, &mut #smodel_path::DeepCloneContext::new(arena))
+            }
+        });
+
+        if smtype == &basemost {
+            self.deep_clone_root(host, smtype, smodel_path);
+        }
+    }
+
+    // Contribute a `__deep_clone` method to the basemost data type, allocating
+    // an object of the same data type and copying the fields from the
+    // basemost data type. The copy is recorded before copying the fields,
+    // preserving circular references.
+    fn deep_clone_root(&self, host: &SModelHost, smtype: &Symbol, smodel_path: &proc_macro2::TokenStream) {
+        let (_, ty_generics, _) = host.split_generics();
+        let smtype_data_name = Ident::new(&format!("{DATA_PREFIX}{}", smtype.name()), Span::call_site());
+        let data_id = Ident::new(DATA, Span::call_site());
+        let tag_enum = tag_enum_name(smtype);
+
+        let mut allocate_arms = proc_macro2::TokenStream::new();
+        let mut copy_arms = proc_macro2::TokenStream::new();
+
+        for subtype in [smtype.clone()].into_iter().chain(smtype.descendant_smtype_list()) {
+            if subtype.is_abstract() {
+                continue;
+            }
+            let subtype_name = Ident::new(&subtype.name(), Span::call_site());
+
+            // Generate `MN(...M2(self.clone()))` and `MN(...M2(copy.clone()))`
+            // layers from the basemost data type to the subtype.
+            let source = proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root("self", &subtype.asc_smtype_list()[1..])).unwrap();
+            let destination = proc_macro2::TokenStream::from_str(&Symbol::create_layers_over_weak_root("copy", &subtype.asc_smtype_list()[1..])).unwrap();
+
+            let mut fields = proc_macro2::TokenStream::new();
+            for m in subtype.asc_smtype_list().iter() {
+                let m_name = Ident::new(&m.name(), Span::call_site());
+                for (field_name, _) in m.fields().borrow().iter() {
+                    let getter = Ident::new(field_name, Span::call_site());
+                    let setter = Ident::new(&format!("set_{field_name}"), Span::call_site());
+                    fields.extend(quote! {
+                        #m_name::#setter(&d, (&&&#smodel_path::DeepCloneField(&#m_name::#getter(&o))).deep_clone_field(cx, |e, cx| (&&#smodel_path::DeepCloneField(e)).deep_clone_element(cx)));
+                    });
+                }
+            }
+
+            allocate_arms.extend(quote! {
+                #tag_enum::#subtype_name => #subtype_name::__new_default(arena).into(),
+            });
+            copy_arms.extend(quote! {
+                #tag_enum::#subtype_name => {
+                    let o = #source;
+                    let d = #destination;
+                    #fields
+                },
+            });
+        }
+
+        smtype.method_output().borrow_mut().extend(quote! {
+            #[allow(unused_variables, unreachable_code, clippy::useless_conversion)]
+            fn __deep_clone(&self, cx: &mut #smodel_path::DeepCloneContext) -> Self where Self: 'static {
+                #[allow(unused_imports)]
+                use #smodel_path::{DeepCloneViaTrait as _, DeepCloneViaContainer as _, DeepCloneViaClone as _, DeepCloneElementViaTrait as _, DeepCloneElementViaClone as _};
+
+                let source = self.0.as_ptr() as *const ();
+                if let Some(copy) = cx.get_copy::<Self>(source) {
+                    return copy;
+                }
+                let arena = cx.arena::<#data_id::#smtype_data_name #ty_generics>();
+                let copy: Self = match self.kind() {
+                    #allocate_arms
+                };
+                cx.set_copy(source, copy.clone());
+                match self.kind() {
+                    #copy_arms
+                }
+                copy
+            }
+        });
+    }
+}
@@ -0,0 +1,124 @@
+use crate::*;
+
+pub struct ProcessingStep4_5();
+
+impl ProcessingStep4_5 {
+    // Implement the trait of a trait implementation, including the instance
+    // methods declared by implementations of the same trait in base data types.
+    pub fn exec(&self, host: &mut SModelHost, imp: &Rc<SmTypeImpl>, smtype: &Symbol, data_types: &[Rc<SmType>]) {
+        let smtype_name = Ident::new(&smtype.name(), Span::call_site());
+        let trait_path = &imp.trait_path;
+        let attr = &imp.attributes;
+
+        // Implementations of the same trait from the basemost
+        // data type to this data type.
+        let impls = smtype.asc_smtype_list().iter()
+            .filter_map(|m| data_types.iter().find(|node| node.name == m.name()))
+            .flat_map(|node| node.impls.iter().filter(|i| i.trait_name == imp.trait_name).cloned().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut items = proc_macro2::TokenStream::new();
+
+        // Associated types and constants, inherited from the most
+        // specific implementation declaring them.
+        let mut item_names: Vec<String> = vec![];
+        for imp1 in impls.iter().rev() {
+            for item in imp1.associated_items.iter() {
+                let item_name = match item {
+                    ImplItem::Type(t) => t.ident.to_string(),
+                    ImplItem::Const(c) => c.ident.to_string(),
+                    _ => continue,
+                };
+                if item_names.contains(&item_name) {
+                    continue;
+                }
+                item_names.push(item_name);
+                items.extend(item.to_token_stream());
+            }
+        }
+
+        // Static methods are not inherited.
+        for node in imp.methods.iter().filter(|m| m.inputs.first().map(|i| !matches!(i, FnArg::Receiver(_))).unwrap_or(true)) {
+            let attr = node.attributes.borrow().clone();
+            let name = &node.name;
+            let generics = &node.generics;
+            let inputs = &node.inputs;
+            let where_clause = &node.generics.where_clause;
+            let result_annotation = node.result_type.as_ref().map(|t| quote! { -> #t });
+            let statements = &node.statements;
+            items.extend(quote! {
+                #(#attr)*
+                fn #name #generics(#inputs) #result_annotation #where_clause {
+                    #statements
+                }
+            });
+        }
+
+        // Instance methods, declared by the most specific implementation.
+        let mut methods: Vec<Rc<SmTypeMethod>> = vec![];
+        for node in impls.iter().flat_map(|imp1| imp1.methods.iter()) {
+            if !matches!(node.inputs.first(), Some(FnArg::Receiver(_))) {
+                continue;
+            }
+            if let Some(i) = methods.iter().position(|m| m.name == node.name) {
+                methods[i] = node.clone();
+            } else {
+                methods.push(node.clone());
+            }
+        }
+        for node in methods.iter() {
+            let Some(slot) = smtype.lookup_method(&format!("{}::{}", imp.trait_name, node.name)) else {
+                continue;
+            };
+            let attr = node.attributes.borrow().clone();
+            let name = &node.name;
+            let generics = &node.generics;
+            let where_clause = &node.generics.where_clause;
+            let result_annotation = node.result_type.as_ref().map(|t| quote! { -> #t });
+
+            // Remove the receiver
+            let inputs = node.inputs.iter().skip(1).cloned().collect::<Punctuated<FnArg, Comma>>();
+            let (inputs, input_args) = bind_function_inputs(&inputs);
+
+            let nondispatch_name_id = Ident::new(&nondispatch_name(&slot.name()), Span::call_site());
+            let dynamic_dispatch = if slot.is_final() {
+                proc_macro2::TokenStream::new()
+            } else {
+                ProcessingStep4_1().generate_dynamic_dispatch(&slot, smtype, &input_args, node.result_type.as_ref())
+            };
+
+            // A method taking `self` by value is invoked on the
+            // data type defining the nondispatch method.
+            let defined_in = slot.defined_in();
+            let (receiver, nondispatch_call) = if !slot.is_by_value() {
+                (quote! { &self }, quote! { self.#nondispatch_name_id(#input_args) })
+            } else if &defined_in == smtype {
+                (quote! { self }, quote! { self.#nondispatch_name_id(#input_args) })
+            } else {
+                let defined_in_id = Ident::new(&defined_in.name(), Span::call_site());
+                (quote! { self }, quote! { #defined_in_id::#nondispatch_name_id(self.into(), #input_args) })
+            };
+
+            items.extend(quote! {
+                #(#attr)*
+                fn #name #generics(#receiver, #inputs) #result_annotation #where_clause {
+                    #dynamic_dispatch
+                    #nondispatch_call
+                }
+            });
+        }
+
+        let (impl_generics, ty_generics, _) = host.split_generics();
+        let predicates = imp.generics.where_clause.iter()
+            .flat_map(|w| w.predicates.iter().map(|p| p.to_token_stream()))
+            .collect::<Vec<_>>();
+        let where_clause = host.where_clause_with(&predicates);
+
+        host.output.extend::<TokenStream>(quote! {
+            #(#attr)*
+            impl #impl_generics #trait_path for #smtype_name #ty_generics #where_clause {
+                #items
+            }
+        }.try_into().unwrap());
+    }
+}
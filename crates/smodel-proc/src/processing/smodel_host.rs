@@ -6,6 +6,18 @@ pub struct SModelHost {
     pub smtype_slots: HashMap<String, Symbol>,
     pub output: TokenStream,
     pub data_output: proc_macro2::TokenStream,
+    /// Whether the `#[smodel(layout = "flat")]` option is given.
+    pub flat_layout: bool,
+    /// Whether the `#[smodel(verbose_panics)]` option is given.
+    pub verbose_panics: bool,
+    /// Whether the `#[smodel(serde)]` option is given.
+    pub serde: bool,
+    /// Whether the `#[smodel(missing_override = "warn")]` option is given.
+    pub warn_missing_override: bool,
+    /// Generic parameters of the basemost data type, shared
+    /// by all data types.
+    pub generics: Generics,
+    pub interfaces: Vec<Rc<SmInterface>>,
 }
 
 impl SModelHost {
@@ -16,6 +28,55 @@ impl SModelHost {
             smtype_slots: HashMap::new(),
             output: TokenStream::new(),
             data_output: proc_macro2::TokenStream::new(),
+            flat_layout: false,
+            verbose_panics: false,
+            serde: false,
+            warn_missing_override: false,
+            generics: Generics::default(),
+            interfaces: vec![],
+        }
+    }
+
+    /// Returns the generics of the model split as in
+    /// `impl #impl_generics M #ty_generics #where_clause`.
+    pub fn split_generics(&self) -> (proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream) {
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        (impl_generics.to_token_stream(), ty_generics.to_token_stream(), where_clause.to_token_stream())
+    }
+
+    /// Returns the where clause of the model with additional predicates.
+    pub fn where_clause_with(&self, predicates: &[proc_macro2::TokenStream]) -> proc_macro2::TokenStream {
+        let mut generics = self.generics.clone();
+        let where_clause = generics.make_where_clause();
+        for p in predicates {
+            where_clause.predicates.push(syn::parse2(p.clone()).unwrap());
+        }
+        if where_clause.predicates.is_empty() {
+            return proc_macro2::TokenStream::new();
+        }
+        where_clause.to_token_stream()
+    }
+
+    /// Returns a `PhantomData` type over the generic parameters,
+    /// used by data structures that would otherwise not use all
+    /// parameters; or `None` if there are no generic parameters.
+    pub fn phantom_type(&self) -> Option<proc_macro2::TokenStream> {
+        if self.generics.params.is_empty() {
+            return None;
+        }
+        let lifetimes = self.generics.lifetimes().map(|p| &p.lifetime);
+        let types = self.generics.type_params().map(|p| &p.ident);
+        Some(quote! { ::std::marker::PhantomData<(#(&#lifetimes (),)* #(#types,)*)> })
+    }
+
+    /// Returns code upgrading a `Weak<#DATA::FirstM>` value, panicking
+    /// if the arena was dropped. `accessed` describes what is accessed
+    /// for the panic message in the `verbose_panics` mode.
+    pub fn upgrade_weak_root(&self, weak: &str, accessed: &str) -> String {
+        if self.verbose_panics {
+            format!("{weak}.upgrade().expect({:?})", format!("Accessed {accessed} after its arena was dropped."))
+        } else {
+            format!("{weak}.upgrade().unwrap()")
         }
     }
 }
\ No newline at end of file
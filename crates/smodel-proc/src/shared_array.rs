@@ -37,7 +37,7 @@ impl<T> SharedArray<T> {
         self.0.borrow_mut().remove(index);
     }
 
-    pub fn _includes(&self, value: &T) -> bool where T: PartialEq {
+    pub fn includes(&self, value: &T) -> bool where T: PartialEq {
         self.0.borrow().contains(value)
     }
 
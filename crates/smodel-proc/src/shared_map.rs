@@ -3,8 +3,12 @@ use std::collections::HashMap;
 use std::hash::Hash;
 use std::rc::Rc;
 
+/// A shared mutable map managed by reference counting.
+///
+/// Entries are iterated in insertion order, so that
+/// the macro expansion is stable across compilations.
 #[derive(Clone)]
-pub struct SharedMap<K, V>(Rc<RefCell<HashMap<K, V>>>);
+pub struct SharedMap<K, V>(Rc<RefCell<Vec<(K, V)>>>);
 
 impl<K, V> PartialEq for SharedMap<K, V> {
     fn eq(&self, other: &Self) -> bool {
@@ -23,23 +27,32 @@ impl<K, V> Hash for SharedMap<K, V> {
 
 impl<K, V> SharedMap<K, V> {
     pub fn new() -> Self {
-        Self(Rc::new(RefCell::new(HashMap::new())))
+        Self(Rc::new(RefCell::new(vec![])))
     }
 
     pub fn get(&self, key: &K) -> Option<V> where K: Eq + Hash, V: Clone {
-        self.0.borrow().get(key).map(|v| v.clone())
+        self.0.borrow().iter().find(|(k, _)| k == key).map(|(_, v)| v.clone())
     }
 
+    /// Sets the value of a key, preserving its position
+    /// if the key is already present.
     pub fn set(&mut self, key: K, value: V) where K: Eq + Hash {
-        self.0.borrow_mut().insert(key, value);
+        let mut entries = self.0.borrow_mut();
+        if let Some(entry) = entries.iter_mut().find(|(k, _)| k == &key) {
+            entry.1 = value;
+        } else {
+            entries.push((key, value));
+        }
     }
 
     pub fn _remove(&mut self, key: &K) -> Option<V> where K: Eq + Hash {
-        self.0.borrow_mut().remove(key)
+        let mut entries = self.0.borrow_mut();
+        let i = entries.iter().position(|(k, _)| k == key)?;
+        Some(entries.remove(i).1)
     }
 
     pub fn has(&self, key: &K) -> bool where K: Eq + Hash {
-        self.0.borrow().contains_key(key)
+        self.0.borrow().iter().any(|(k, _)| k == key)
     }
 
     pub fn _length(&self) -> usize {
@@ -54,7 +67,8 @@ impl<K, V> SharedMap<K, V> {
         r
     }
 
-    pub fn borrow(&self) -> std::cell::Ref<HashMap<K, V>> {
+    /// Borrows the entries in insertion order.
+    pub fn borrow(&self) -> std::cell::Ref<Vec<(K, V)>> {
         self.0.borrow()
     }
 }
@@ -29,11 +29,15 @@ impl LmtFactory {
         }
     }
 
-    pub fn create_smtype_slot(&self, name: String) -> Symbol {
+    pub fn create_smtype_slot(&self, name: String, is_abstract: bool, is_final: bool, is_debug: bool) -> Symbol {
         Symbol(self.arena.allocate(Symbol1::SmTypeSlot(Rc::new(SmTypeSlot1 {
             name,
+            is_abstract,
+            is_final,
+            is_debug,
             inherits: RefCell::new(None),
             subtypes: shared_array![],
+            interfaces: shared_array![],
             fields: shared_map![],
             methods: shared_map![],
             method_output: Rc::new(RefCell::new(proc_macro2::TokenStream::new())),
@@ -49,12 +53,15 @@ impl LmtFactory {
         }))))
     }
 
-    pub fn create_method_slot(&self, name: String, defined_in: Symbol, doc_attribute: Vec<syn::Attribute>) -> Symbol {
+    pub fn create_method_slot(&self, name: String, defined_in: Symbol, doc_attribute: Vec<syn::Attribute>, is_abstract: bool, is_final: bool, signature: MethodSignature) -> Symbol {
         Symbol(self.arena.allocate(Symbol1::MethodSlot(Rc::new(MethodSlot1 {
             name,
             defined_in,
+            is_abstract,
+            is_final,
+            signature,
             doc_attribute: RefCell::new(doc_attribute),
-            override_logic_mapping: SharedMap::new(),
+            overrides: RefCell::new(None),
         }))))
     }
 }
@@ -102,6 +109,45 @@ impl Symbol {
         }
     }
 
+    pub fn is_abstract(&self) -> bool {
+        match access!(self) {
+            Symbol1::SmTypeSlot(slot) => slot.is_abstract,
+            Symbol1::MethodSlot(slot) => slot.is_abstract,
+            _ => panic!(),
+        }
+    }
+
+    pub fn is_final(&self) -> bool {
+        match access!(self) {
+            Symbol1::SmTypeSlot(slot) => slot.is_final,
+            Symbol1::MethodSlot(slot) => slot.is_final,
+            _ => panic!(),
+        }
+    }
+
+    /// Whether the method takes a `self` receiver by value.
+    pub fn is_by_value(&self) -> bool {
+        match access!(self) {
+            Symbol1::MethodSlot(slot) => slot.signature.is_by_value,
+            _ => panic!(),
+        }
+    }
+
+    pub fn signature(&self) -> MethodSignature {
+        match access!(self) {
+            Symbol1::MethodSlot(slot) => slot.signature.clone(),
+            _ => panic!(),
+        }
+    }
+
+    /// Whether the data type has the `#[smodel(debug)]` option.
+    pub fn is_debug(&self) -> bool {
+        match access!(self) {
+            Symbol1::SmTypeSlot(slot) => slot.is_debug,
+            _ => panic!(),
+        }
+    }
+
     pub fn inherits(&self) -> Option<Symbol> {
         match access!(self) {
             Symbol1::SmTypeSlot(slot) => slot.inherits.borrow().clone(),
@@ -128,11 +174,12 @@ impl Symbol {
         out
     }
 
-    /// Returns `MN(M2(M1(...)))` layers over a root `Weak<#DATA::FirstM>` value.
+    /// Returns `MN(M2(M1(...)))` layers over a `base` value.
     /// 
     /// Parameters:
     /// 
-    /// * `base`: A `Weak<#DATA::FirstM>` value.
+    /// * `base`: A value of the data type inherited by `M1`,
+    ///   such as the basemost data type.
     pub fn create_layers_over_weak_root(base: &str, asc_smtype_list: &[Symbol]) -> String {
         let mut layers = String::new();
         let mut parens = 0usize;
@@ -159,6 +206,26 @@ impl Symbol {
         None
     }
 
+    /// Looks up the most specific method of a given name, starting
+    /// at this data type and proceeding to its base data types.
+    pub fn lookup_method(&self, name: &str) -> Option<Symbol> {
+        if let Some(mt) = self.methods().get(&name.to_owned()) {
+            return Some(mt);
+        }
+        self.lookup_method_in_base_smtype(name)
+    }
+
+    /// Returns the subtypes of this data type, recursively,
+    /// in depth-first order.
+    pub fn descendant_smtype_list(&self) -> Vec<Symbol> {
+        let mut out = vec![];
+        for m in self.subtypes().iter() {
+            out.push(m.clone());
+            out.extend(m.descendant_smtype_list());
+        }
+        out
+    }
+
     pub fn subtypes(&self) -> SharedArray<Symbol> {
         match access!(self) {
             Symbol1::SmTypeSlot(slot) => slot.subtypes.clone(),
@@ -166,6 +233,15 @@ impl Symbol {
         }
     }
 
+    /// The names of the interfaces the data type lists,
+    /// not including those of base data types.
+    pub fn interfaces(&self) -> SharedArray<String> {
+        match access!(self) {
+            Symbol1::SmTypeSlot(slot) => slot.interfaces.clone(),
+            _ => panic!(),
+        }
+    }
+
     pub fn fields(&self) -> SharedMap<String, Symbol> {
         match access!(self) {
             Symbol1::SmTypeSlot(slot) => slot.fields.clone(),
@@ -229,12 +305,34 @@ impl Symbol {
         }
     }
 
-    pub fn override_logic_mapping(&self) -> SharedMap<Symbol, Rc<OverrideLogicMapping>> {
+    pub fn overrides(&self) -> Option<Symbol> {
+        match access!(self) {
+            Symbol1::MethodSlot(slot) => slot.overrides.borrow().clone(),
+            _ => panic!(),
+        }
+    }
+
+    pub fn set_overrides(&self, value: Option<&Symbol>) {
         match access!(self) {
-            Symbol1::MethodSlot(slot) => slot.override_logic_mapping.clone(),
+            Symbol1::MethodSlot(slot) => {
+                slot.overrides.replace(value.map(|v| v.clone()));
+            },
             _ => panic!(),
         }
     }
+
+    /// Determines whether this method is `base_method` or
+    /// overrides it directly or indirectly.
+    pub fn overrides_transitively(&self, base_method: &Symbol) -> bool {
+        let mut m = Some(self.clone());
+        while let Some(m1) = m {
+            if &m1 == base_method {
+                return true;
+            }
+            m = m1.overrides();
+        }
+        false
+    }
 }
 
 impl ToString for Symbol {
@@ -243,6 +341,17 @@ impl ToString for Symbol {
     }
 }
 
+/// The signature of an instance method, excluding its name and receiver
+/// parameter, compared against overriding methods.
+#[derive(Clone)]
+pub struct MethodSignature {
+    pub visibility: syn::Visibility,
+    pub generics: syn::Generics,
+    pub is_by_value: bool,
+    pub parameter_types: Vec<syn::Type>,
+    pub result_type: Option<syn::Type>,
+}
+
 enum Symbol1 {
     SmTypeSlot(Rc<SmTypeSlot1>),
     FieldSlot(Rc<FieldSlot1>),
@@ -251,8 +360,12 @@ enum Symbol1 {
 
 struct SmTypeSlot1 {
     name: String,
+    is_abstract: bool,
+    is_final: bool,
+    is_debug: bool,
     inherits: RefCell<Option<Symbol>>,
     subtypes: SharedArray<Symbol>,
+    interfaces: SharedArray<String>,
     fields: SharedMap<String, Symbol>,
     methods: SharedMap<String, Symbol>,
     method_output: Rc<RefCell<proc_macro2::TokenStream>>,
@@ -268,37 +381,11 @@ struct FieldSlot1 {
 struct MethodSlot1 {
     name: String,
     defined_in: Symbol,
+    is_abstract: bool,
+    is_final: bool,
+    signature: MethodSignature,
     doc_attribute: RefCell<Vec<syn::Attribute>>,
-    override_logic_mapping: SharedMap<Symbol, Rc<OverrideLogicMapping>>,
-}
-
-pub struct OverrideLogicMapping {
-    override_code: RefCell<Option<proc_macro2::TokenStream>>,
-    override_logic_mapping: SharedMap<Symbol, Rc<OverrideLogicMapping>>,
-}
-
-impl OverrideLogicMapping {
-    pub fn new() -> Self {
-        Self {
-            override_code: RefCell::new(None),
-            override_logic_mapping: SharedMap::new(),
-        }
-    }
-
-    /// Override code; generally a `return` statement with a semicolon.
-    pub fn override_code(&self) -> Option<proc_macro2::TokenStream> {
-        self.override_code.borrow().clone()
-    }
-
-    /// Sets override code; generally a `return` statement with a semicolon.
-    pub fn set_override_code(&self, code: Option<proc_macro2::TokenStream>) {
-        self.override_code.replace(code);
-    }
-
-    /// Mapping from subtype slot to override logic.
-    pub fn override_logic_mapping(&self) -> SharedMap<Symbol, Rc<OverrideLogicMapping>> {
-        self.override_logic_mapping.clone()
-    }
+    overrides: RefCell<Option<Symbol>>,
 }
 
 /// A data type slot.
@@ -307,9 +394,12 @@ impl OverrideLogicMapping {
 /// 
 /// * `is_smtype_slot()` — Returns `true`.
 /// * `name()`
+/// * `is_abstract()`
+/// * `is_final()`
 /// * `inherits()`
 /// * `set_inherits()`
 /// * `subtypes()`
+/// * `interfaces()`
 /// * `fields()`
 /// * `methods()`
 /// * `method_output()` — The contents of the `impl` block of the data type.
@@ -350,10 +440,15 @@ impl Deref for FieldSlot {
 /// 
 /// * `is_method_slot()` — Returns `true`.
 /// * `name()`
+/// * `is_abstract()`
+/// * `is_final()`
+/// * `is_by_value()` — Whether the method takes `self` by value.
+/// * `signature()`
 /// * `defined_in()`
 /// * `doc_attribute()`
 /// * `set_doc_attribute()`
-/// * `override_logic_mapping()` — Mapping from subtype slot to override logic.
+/// * `overrides()` — The base method slot that this method overrides.
+/// * `set_overrides()`
 #[derive(Clone, Hash, PartialEq, Eq)]
 pub struct MethodSlot(pub Symbol);
 
@@ -0,0 +1,144 @@
+use std::{any::Any, collections::HashMap, hash::Hash};
+use crate::Arena;
+
+/// Copies a value into an arena, copying the objects it refers to.
+///
+/// This trait is implemented by every data type and by common
+/// containers of values implementing `DeepClone`. Field values
+/// not implementing `DeepClone` are cloned through `Clone`.
+pub trait DeepClone: Sized {
+    fn deep_clone(&self, cx: &mut DeepCloneContext) -> Self;
+}
+
+/// Context of a deep clone, holding the destination arena and a memo
+/// table from copied objects to their copies, which preserves shared
+/// and circular references.
+///
+/// A context may be reused for copying several values that refer
+/// to the same objects.
+pub struct DeepCloneContext<'a> {
+    arena: &'a dyn Any,
+    memo: HashMap<*const (), Box<dyn Any>>,
+}
+
+impl<'a> DeepCloneContext<'a> {
+    pub fn new<T: 'static>(arena: &'a Arena<T>) -> Self {
+        Self {
+            arena,
+            memo: HashMap::new(),
+        }
+    }
+
+    /// Returns the destination arena.
+    #[doc(hidden)]
+    pub fn arena<T: 'static>(&self) -> &'a Arena<T> {
+        self.arena.downcast_ref().expect("Deep cloning into an arena of another model.")
+    }
+
+    /// Returns the copy of the value at a given address.
+    #[doc(hidden)]
+    pub fn get_copy<T: Clone + 'static>(&self, source: *const ()) -> Option<T> {
+        self.memo.get(&source).and_then(|v| v.downcast_ref::<T>()).cloned()
+    }
+
+    /// Records the copy of the value at a given address.
+    #[doc(hidden)]
+    pub fn set_copy<T: 'static>(&mut self, source: *const (), copy: T) {
+        self.memo.insert(source, Box::new(copy));
+    }
+}
+
+impl<T: DeepClone> DeepClone for Option<T> {
+    fn deep_clone(&self, cx: &mut DeepCloneContext) -> Self {
+        self.as_ref().map(|v| v.deep_clone(cx))
+    }
+}
+
+impl<T: DeepClone> DeepClone for Vec<T> {
+    fn deep_clone(&self, cx: &mut DeepCloneContext) -> Self {
+        self.iter().map(|v| v.deep_clone(cx)).collect()
+    }
+}
+
+impl<T: DeepClone> DeepClone for Box<T> {
+    fn deep_clone(&self, cx: &mut DeepCloneContext) -> Self {
+        Box::new(self.as_ref().deep_clone(cx))
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: DeepClone> DeepClone for HashMap<K, V> {
+    fn deep_clone(&self, cx: &mut DeepCloneContext) -> Self {
+        self.iter().map(|(k, v)| (k.clone(), v.deep_clone(cx))).collect()
+    }
+}
+
+/// A container copied into a new container, copying each
+/// element through a given function.
+#[doc(hidden)]
+pub trait DeepCloneContainer: Sized {
+    type Element;
+    fn deep_clone_with(&self, cx: &mut DeepCloneContext, element: impl FnMut(&Self::Element, &mut DeepCloneContext) -> Self::Element) -> Self;
+}
+
+/// Wraps a field value for deep cloning it through `DeepClone`; otherwise,
+/// if it is a shared container, into a new container; otherwise through `Clone`.
+///
+/// Elements of a shared container are copied through `DeepClone`,
+/// or otherwise through `Clone`.
+#[doc(hidden)]
+pub struct DeepCloneField<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait DeepCloneViaTrait<T> {
+    fn deep_clone_field(&self, cx: &mut DeepCloneContext, element: impl FnMut(&T, &mut DeepCloneContext) -> T) -> T;
+}
+
+impl<T: DeepClone> DeepCloneViaTrait<T> for &&DeepCloneField<'_, T> {
+    fn deep_clone_field(&self, cx: &mut DeepCloneContext, _element: impl FnMut(&T, &mut DeepCloneContext) -> T) -> T {
+        self.0.deep_clone(cx)
+    }
+}
+
+#[doc(hidden)]
+pub trait DeepCloneViaContainer<T: DeepCloneContainer> {
+    fn deep_clone_field(&self, cx: &mut DeepCloneContext, element: impl FnMut(&T::Element, &mut DeepCloneContext) -> T::Element) -> T;
+}
+
+impl<T: DeepCloneContainer> DeepCloneViaContainer<T> for &DeepCloneField<'_, T> {
+    fn deep_clone_field(&self, cx: &mut DeepCloneContext, element: impl FnMut(&T::Element, &mut DeepCloneContext) -> T::Element) -> T {
+        self.0.deep_clone_with(cx, element)
+    }
+}
+
+#[doc(hidden)]
+pub trait DeepCloneViaClone<T> {
+    fn deep_clone_field(&self, cx: &mut DeepCloneContext, element: impl FnMut(&T, &mut DeepCloneContext) -> T) -> T;
+}
+
+impl<T: Clone> DeepCloneViaClone<T> for DeepCloneField<'_, T> {
+    fn deep_clone_field(&self, _cx: &mut DeepCloneContext, _element: impl FnMut(&T, &mut DeepCloneContext) -> T) -> T {
+        self.0.clone()
+    }
+}
+
+#[doc(hidden)]
+pub trait DeepCloneElementViaTrait<T> {
+    fn deep_clone_element(&self, cx: &mut DeepCloneContext) -> T;
+}
+
+impl<T: DeepClone> DeepCloneElementViaTrait<T> for &DeepCloneField<'_, T> {
+    fn deep_clone_element(&self, cx: &mut DeepCloneContext) -> T {
+        self.0.deep_clone(cx)
+    }
+}
+
+#[doc(hidden)]
+pub trait DeepCloneElementViaClone<T> {
+    fn deep_clone_element(&self, cx: &mut DeepCloneContext) -> T;
+}
+
+impl<T: Clone> DeepCloneElementViaClone<T> for DeepCloneField<'_, T> {
+    fn deep_clone_element(&self, _cx: &mut DeepCloneContext) -> T {
+        self.0.clone()
+    }
+}
@@ -1,10 +1,21 @@
-use std::{cell::RefCell, rc::{Rc, Weak}};
+use std::{cell::RefCell, rc::Rc};
 use std::fmt::Debug;
 
 pub mod util;
 
 pub use smodel_proc::smodel;
 
+mod deep_clone;
+pub use deep_clone::*;
+
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "serde")]
+pub use serialization::*;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+
 pub struct Arena<T> {
     data: RefCell<Vec<Rc<T>>>,
 }
@@ -16,16 +27,135 @@ impl<T> Arena<T> {
         }
     }
 
-    pub fn allocate(&self, value: T) -> Weak<T> {
+    /// Allocates an object, assigning it an ID equals to
+    /// the number of objects previously allocated, and
+    /// returns it as the basemost data type.
+    pub fn allocate<M>(&self, mut value: T) -> M where T: ArenaData<M> {
+        let mut data = self.data.borrow_mut();
+        value.set_id(data.len());
         let obj = Rc::new(value);
-        self.data.borrow_mut().push(obj.clone());
-        Rc::downgrade(&obj)
+        data.push(obj.clone());
+        T::handle(&obj)
+    }
+
+    /// Returns the object of a given ID as the basemost data type.
+    pub fn get_by_id<M>(&self, id: usize) -> Option<M> where T: ArenaData<M> {
+        self.data.borrow().get(id).map(T::handle)
+    }
+
+    /// Returns the number of objects allocated in the arena.
+    pub fn len(&self) -> usize {
+        self.data.borrow().len()
+    }
+
+    /// Determines whether no object has been allocated in the arena.
+    pub fn is_empty(&self) -> bool {
+        self.data.borrow().is_empty()
     }
+
+    /// Iterates the objects allocated in the arena, in allocation order,
+    /// as the basemost data type.
+    ///
+    /// Objects allocated during iteration are not visited.
+    pub fn iter<M>(&self) -> std::vec::IntoIter<M> where T: ArenaData<M> {
+        self.data.borrow().iter().map(T::handle).collect::<Vec<_>>().into_iter()
+    }
+
+    /// Iterates the objects allocated in the arena, in allocation order,
+    /// that are of the data type `S`.
+    ///
+    /// Objects allocated during iteration are not visited.
+    pub fn iter_of<S: ArenaSubtype<T>>(&self) -> std::vec::IntoIter<S> {
+        self.data.borrow().iter().filter_map(S::from_arena_data).collect::<Vec<_>>().into_iter()
+    }
+}
+
+/// Implemented by the data structure of the basemost data type `M`
+/// of a semantic model, which is the one allocated in the arena.
+pub trait ArenaData<M> {
+    /// Assigns the object ID.
+    fn set_id(&mut self, id: usize);
+
+    /// Returns the basemost data type for a given object.
+    fn handle(data: &Rc<Self>) -> M;
+}
+
+/// Implemented by every data type of a semantic model whose
+/// basemost data structure is `T`.
+pub trait ArenaSubtype<T>: Sized {
+    /// Converts an object allocated in the arena to this data type,
+    /// returning `None` if the object is not of this data type.
+    fn from_arena_data(data: &Rc<T>) -> Option<Self>;
+}
+
+/// Implemented by every interface of a semantic model whose
+/// basemost data type is `M`.
+pub trait Interface<M>: Sized {
+    /// Converts an object to this interface, returning `None` if
+    /// the object's data type does not implement this interface.
+    fn from_object(object: M) -> Option<Self>;
 }
 
 #[derive(Debug)]
 pub enum SModelError {
-    Contravariant,
+    /// A contravariant conversion failed.
+    Contravariant {
+        /// The static data type converted from.
+        from: &'static str,
+        /// The data type requested.
+        to: &'static str,
+        /// The data type the object was constructed as.
+        actual: &'static str,
+    },
+    /// The arena of the accessed object was dropped.
+    Dangling,
+}
+
+impl std::fmt::Display for SModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Contravariant { from, to, actual } => {
+                write!(f, "Cannot convert '{from}' to '{to}'; the object is a '{actual}'.")
+            },
+            Self::Dangling => {
+                write!(f, "The arena of the object was dropped.")
+            },
+        }
+    }
+}
+
+impl std::error::Error for SModelError {}
+
+thread_local! {
+    static DEBUG_STACK: RefCell<Vec<*const ()>> = const { RefCell::new(vec![]) };
+}
+
+/// Runs `f` unless the object is already being written by an enclosing
+/// field-aware `Debug`, in which case `None` is returned. Used for
+/// protecting against cycles.
+#[doc(hidden)]
+pub fn debug_guard<R>(object: *const (), f: impl FnOnce() -> R) -> Option<R> {
+    struct Pop;
+    impl Drop for Pop {
+        fn drop(&mut self) {
+            DEBUG_STACK.with(|stack| stack.borrow_mut().pop());
+        }
+    }
+
+    let entered = DEBUG_STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        if stack.contains(&object) {
+            false
+        } else {
+            stack.push(object);
+            true
+        }
+    });
+    if !entered {
+        return None;
+    }
+    let _pop = Pop;
+    Some(f())
 }
 
 #[cfg(test)]
@@ -160,4 +290,1836 @@ mod test {
         assert_eq!(true, base_symbol.is::<FooQux>());
         assert_eq!(0.0, base_symbol.x());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn abstract_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            abstract struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+
+                pub abstract fn name(&self) -> String;
+
+                pub fn describe(&self) -> String {
+                    format!("thingy {}", self.name())
+                }
+            }
+
+            abstract struct Named: Thingy {
+                pub fn Named() {
+                    super();
+                }
+
+                pub abstract fn label(&self, uppercase: bool) -> String;
+            }
+
+            struct Foo: Named {
+                pub fn Foo() {
+                    super();
+                }
+
+                pub override fn name(&self) -> String {
+                    "Foo".into()
+                }
+
+                pub override fn label(&self, uppercase: bool) -> String {
+                    if uppercase { "FOO".into() } else { "foo".into() }
+                }
+            }
+
+            struct FooBar: Foo {
+                pub fn FooBar() {
+                    super();
+                }
+
+                pub override fn name(&self) -> String {
+                    format!("{}Bar", super.name())
+                }
+            }
+        }
+
+        let arena = Arena::new();
+
+        let base_symbol: Thingy = Foo::new(&arena).into();
+        assert_eq!("Foo", base_symbol.name());
+        assert_eq!("thingy Foo", base_symbol.describe());
+        assert_eq!("FOO", base_symbol.to::<Named>().unwrap().label(true));
+
+        let base_symbol: Thingy = FooBar::new(&arena).into();
+        assert_eq!("FooBar", base_symbol.name());
+        assert_eq!("foo", base_symbol.to::<Named>().unwrap().label(false));
+    }
+
+    #[test]
+    fn final_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                let ref m_name: String = "".into();
+
+                pub fn Thingy() {
+                    super();
+                }
+
+                pub final fn name(&self) -> String {
+                    self.m_name()
+                }
+
+                pub fn description(&self) -> String {
+                    "thingy".into()
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo(name: &str) {
+                    super();
+                    self.set_m_name(name.into());
+                }
+
+                pub final override fn description(&self) -> String {
+                    "foo".into()
+                }
+            }
+
+            final struct FooBar: Foo {
+                pub fn FooBar(name: &str) {
+                    super(name);
+                }
+            }
+        }
+
+        let arena = Arena::new();
+
+        let base_symbol: Thingy = FooBar::new(&arena, "x").into();
+        assert_eq!("x", base_symbol.name());
+        assert_eq!("foo", base_symbol.description());
+    }
+
+    #[test]
+    fn definition_order_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct FooBar: Foo {
+                pub fn FooBar() {
+                    super();
+                }
+
+                pub override fn name(&self) -> String {
+                    "FooBar".into()
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+
+                pub override fn name(&self) -> String {
+                    "Foo".into()
+                }
+            }
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+
+                pub fn name(&self) -> String {
+                    "".into()
+                }
+            }
+        }
+
+        let arena = Arena::new();
+
+        let base_symbol: Thingy = FooBar::new(&arena).into();
+        assert_eq!("FooBar", base_symbol.name());
+        assert_eq!(true, base_symbol.is::<Foo>());
+    }
+
+    /// Compares the tag-based dynamic dispatch against the former strategy,
+    /// where each data type tested its subtypes through `is::<T>()` and
+    /// `is::<T>()` walked the nested `__variant` enums. The `old_*` functions
+    /// reproduce the code formerly generated for `value()` and `try_from()`.
+    #[test]
+    fn dispatch_benchmark_test() {
+        use crate::smodel;
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+
+                pub fn value(&self) -> u32 {
+                    0
+                }
+            }
+
+            struct L1: Thingy {
+                pub fn L1() {
+                    super();
+                }
+
+                pub override fn value(&self) -> u32 {
+                    1
+                }
+            }
+
+            struct L2: L1 {
+                pub fn L2() {
+                    super();
+                }
+
+                pub override fn value(&self) -> u32 {
+                    2
+                }
+            }
+
+            struct L3: L2 {
+                pub fn L3() {
+                    super();
+                }
+
+                pub override fn value(&self) -> u32 {
+                    3
+                }
+            }
+
+            struct L3b: L2 {
+                pub fn L3b() {
+                    super();
+                }
+
+                pub override fn value(&self) -> u32 {
+                    30
+                }
+            }
+
+            struct L4: L3 {
+                pub fn L4() {
+                    super();
+                }
+
+                pub override fn value(&self) -> u32 {
+                    4
+                }
+            }
+
+            struct L5: L4 {
+                pub fn L5() {
+                    super();
+                }
+
+                pub override fn value(&self) -> u32 {
+                    5
+                }
+            }
+
+            struct L5b: L4 {
+                pub fn L5b() {
+                    super();
+                }
+
+                pub override fn value(&self) -> u32 {
+                    50
+                }
+            }
+
+            struct L6: L5 {
+                pub fn L6() {
+                    super();
+                }
+
+                pub override fn value(&self) -> u32 {
+                    6
+                }
+            }
+        }
+
+        fn old_try_from_thingy_to_l1(v: Thingy) -> Result<L1, ()> {
+            if let __data__::__variant_Thingy::__data_L1(_o) = &v.0.upgrade().unwrap().__variant {
+                Ok(L1(v.clone()))
+            } else { Err(()) }
+        }
+
+        fn old_try_from_l1_to_l2(v: L1) -> Result<L2, ()> {
+            if let __data__::__variant_Thingy::__data_L1(_o) = &v.0.0.upgrade().unwrap().__variant {
+                if let __data__::__variant_L1::__data_L2(_o) = &_o.__variant {
+                    Ok(L2(L1(v.0.clone())))
+                } else { Err(()) }
+            } else { Err(()) }
+        }
+
+        fn old_try_from_l2_to_l3(v: L2) -> Result<L3, ()> {
+            if let __data__::__variant_Thingy::__data_L1(_o) = &v.0.0.0.upgrade().unwrap().__variant {
+                if let __data__::__variant_L1::__data_L2(_o) = &_o.__variant {
+                    if let __data__::__variant_L2::__data_L3(_o) = &_o.__variant {
+                        Ok(L3(L2(L1(v.0.0.clone()))))
+                    } else { Err(()) }
+                } else { Err(()) }
+            } else { Err(()) }
+        }
+
+        fn old_try_from_l2_to_l3b(v: L2) -> Result<L3b, ()> {
+            if let __data__::__variant_Thingy::__data_L1(_o) = &v.0.0.0.upgrade().unwrap().__variant {
+                if let __data__::__variant_L1::__data_L2(_o) = &_o.__variant {
+                    if let __data__::__variant_L2::__data_L3b(_o) = &_o.__variant {
+                        Ok(L3b(L2(L1(v.0.0.clone()))))
+                    } else { Err(()) }
+                } else { Err(()) }
+            } else { Err(()) }
+        }
+
+        fn old_try_from_l3_to_l4(v: L3) -> Result<L4, ()> {
+            if let __data__::__variant_Thingy::__data_L1(_o) = &v.0.0.0.0.upgrade().unwrap().__variant {
+                if let __data__::__variant_L1::__data_L2(_o) = &_o.__variant {
+                    if let __data__::__variant_L2::__data_L3(_o) = &_o.__variant {
+                        if let __data__::__variant_L3::__data_L4(_o) = &_o.__variant {
+                            Ok(L4(L3(L2(L1(v.0.0.0.clone())))))
+                        } else { Err(()) }
+                    } else { Err(()) }
+                } else { Err(()) }
+            } else { Err(()) }
+        }
+
+        fn old_try_from_l4_to_l5(v: L4) -> Result<L5, ()> {
+            if let __data__::__variant_Thingy::__data_L1(_o) = &v.0.0.0.0.0.upgrade().unwrap().__variant {
+                if let __data__::__variant_L1::__data_L2(_o) = &_o.__variant {
+                    if let __data__::__variant_L2::__data_L3(_o) = &_o.__variant {
+                        if let __data__::__variant_L3::__data_L4(_o) = &_o.__variant {
+                            if let __data__::__variant_L4::__data_L5(_o) = &_o.__variant {
+                                Ok(L5(L4(L3(L2(L1(v.0.0.0.0.clone()))))))
+                            } else { Err(()) }
+                        } else { Err(()) }
+                    } else { Err(()) }
+                } else { Err(()) }
+            } else { Err(()) }
+        }
+
+        fn old_try_from_l4_to_l5b(v: L4) -> Result<L5b, ()> {
+            if let __data__::__variant_Thingy::__data_L1(_o) = &v.0.0.0.0.0.upgrade().unwrap().__variant {
+                if let __data__::__variant_L1::__data_L2(_o) = &_o.__variant {
+                    if let __data__::__variant_L2::__data_L3(_o) = &_o.__variant {
+                        if let __data__::__variant_L3::__data_L4(_o) = &_o.__variant {
+                            if let __data__::__variant_L4::__data_L5b(_o) = &_o.__variant {
+                                Ok(L5b(L4(L3(L2(L1(v.0.0.0.0.clone()))))))
+                            } else { Err(()) }
+                        } else { Err(()) }
+                    } else { Err(()) }
+                } else { Err(()) }
+            } else { Err(()) }
+        }
+
+        fn old_try_from_l5_to_l6(v: L5) -> Result<L6, ()> {
+            if let __data__::__variant_Thingy::__data_L1(_o) = &v.0.0.0.0.0.0.upgrade().unwrap().__variant {
+                if let __data__::__variant_L1::__data_L2(_o) = &_o.__variant {
+                    if let __data__::__variant_L2::__data_L3(_o) = &_o.__variant {
+                        if let __data__::__variant_L3::__data_L4(_o) = &_o.__variant {
+                            if let __data__::__variant_L4::__data_L5(_o) = &_o.__variant {
+                                if let __data__::__variant_L5::__data_L6(_o) = &_o.__variant {
+                                    Ok(L6(L5(L4(L3(L2(L1(v.0.0.0.0.0.clone())))))))
+                                } else { Err(()) }
+                            } else { Err(()) }
+                        } else { Err(()) }
+                    } else { Err(()) }
+                } else { Err(()) }
+            } else { Err(()) }
+        }
+
+        fn old_value_thingy(o: &Thingy) -> u32 {
+            if old_try_from_thingy_to_l1(o.clone()).is_ok() { return old_value_l1(&L1(o.clone())); }
+            o.__nd_value()
+        }
+
+        fn old_value_l1(o: &L1) -> u32 {
+            if old_try_from_l1_to_l2(o.clone()).is_ok() { return old_value_l2(&L2(o.clone())); }
+            o.__nd_value()
+        }
+
+        fn old_value_l2(o: &L2) -> u32 {
+            if old_try_from_l2_to_l3(o.clone()).is_ok() { return old_value_l3(&L3(o.clone())); }
+            if old_try_from_l2_to_l3b(o.clone()).is_ok() { return L3b(o.clone()).__nd_value(); }
+            o.__nd_value()
+        }
+
+        fn old_value_l3(o: &L3) -> u32 {
+            if old_try_from_l3_to_l4(o.clone()).is_ok() { return old_value_l4(&L4(o.clone())); }
+            o.__nd_value()
+        }
+
+        fn old_value_l4(o: &L4) -> u32 {
+            if old_try_from_l4_to_l5(o.clone()).is_ok() { return old_value_l5(&L5(o.clone())); }
+            if old_try_from_l4_to_l5b(o.clone()).is_ok() { return L5b(o.clone()).__nd_value(); }
+            o.__nd_value()
+        }
+
+        fn old_value_l5(o: &L5) -> u32 {
+            if old_try_from_l5_to_l6(o.clone()).is_ok() { return L6(o.clone()).__nd_value(); }
+            o.__nd_value()
+        }
+
+        let arena = Arena::new();
+        let symbols: Vec<Thingy> = vec![
+            Thingy::new(&arena),
+            L1::new(&arena).into(),
+            L2::new(&arena).into(),
+            L3::new(&arena).into(),
+            L3b::new(&arena).into(),
+            L4::new(&arena).into(),
+            L5::new(&arena).into(),
+            L5b::new(&arena).into(),
+            L6::new(&arena).into(),
+        ];
+        let iterations = 2_000;
+
+        let start = Instant::now();
+        let mut old_results = vec![];
+        for _ in 0..iterations {
+            for symbol in symbols.iter() {
+                old_results.push(black_box(old_value_thingy(black_box(symbol))));
+            }
+        }
+        let old_elapsed = start.elapsed();
+
+        let start = Instant::now();
+        let mut tag_results = vec![];
+        for _ in 0..iterations {
+            for symbol in symbols.iter() {
+                tag_results.push(black_box(black_box(symbol).value()));
+            }
+        }
+        let tag_elapsed = start.elapsed();
+
+        assert_eq!(vec![0, 1, 2, 3, 30, 4, 5, 50, 6], tag_results[..symbols.len()]);
+        assert_eq!(old_results, tag_results);
+        assert!(tag_elapsed < old_elapsed);
+    }
+
+    #[test]
+    fn flat_layout_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            #[smodel(layout = "flat")]
+            struct Thingy {
+                let x: f64 = 1.0;
+
+                pub fn Thingy() {
+                    super();
+                }
+
+                pub fn sum(&self) -> f64 {
+                    self.x()
+                }
+            }
+
+            struct Foo: Thingy {
+                let ref y: String = "y".into();
+
+                pub fn Foo() {
+                    super();
+                }
+            }
+
+            struct FooBar: Foo {
+                let z: f64 = 3.0;
+
+                pub fn FooBar(z: f64) {
+                    super();
+                    self.set_z(z);
+                }
+
+                pub override fn sum(&self) -> f64 {
+                    super.sum() + self.z()
+                }
+            }
+        }
+
+        let arena = Arena::new();
+
+        let symbol = FooBar::new(&arena, 5.0);
+        symbol.set_x(2.0);
+        assert_eq!(2.0, symbol.x());
+        assert_eq!("y", symbol.y());
+        assert_eq!(5.0, symbol.z());
+
+        let base_symbol: Thingy = symbol.into();
+        assert_eq!(7.0, base_symbol.sum());
+        assert_eq!(true, base_symbol.is::<FooBar>());
+        assert_eq!(5.0, base_symbol.to::<FooBar>().unwrap().z());
+        assert_eq!(false, Thingy::new(&arena).is::<Foo>());
+    }
+
+    #[test]
+    fn id_test() {
+        use crate::smodel;
+        use std::collections::BTreeSet;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+            }
+        }
+
+        let arena = Arena::new();
+
+        let thingy = Thingy::new(&arena);
+        let foo = Foo::new(&arena);
+        let foo2 = Foo::new(&arena);
+        assert_eq!(0, thingy.id());
+        assert_eq!(1, foo.id());
+        assert_eq!(2, foo2.id());
+
+        assert_eq!(Some(thingy.clone()), arena.get_by_id(0));
+        assert_eq!(Some(Thingy::from(foo.clone())), arena.get_by_id(1));
+        assert_eq!(true, arena.get_by_id(2).unwrap().is::<Foo>());
+        assert_eq!(None, arena.get_by_id(3));
+
+        let set: BTreeSet<Thingy> = [foo2.clone().into(), thingy.clone(), foo.clone().into(), thingy.clone()].into_iter().collect();
+        assert_eq!(vec![0, 1, 2], set.iter().map(|m| m.id()).collect::<Vec<_>>());
+        assert!(foo < foo2);
+    }
+
+    #[test]
+    fn cross_arena_identity_test() {
+        use crate::smodel;
+        use std::collections::{BTreeSet, HashSet};
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+            }
+        }
+
+        let arena1 = Arena::new();
+        let arena2 = Arena::new();
+        let x = Thingy::new(&arena1);
+        let y = Thingy::new(&arena2);
+        assert_eq!(x.id(), y.id());
+        assert_ne!(x, y);
+        assert_ne!(std::cmp::Ordering::Equal, x.cmp(&y));
+        assert_eq!(2, [x.clone(), y.clone()].into_iter().collect::<BTreeSet<_>>().len());
+        assert_eq!(2, [x.clone(), y.clone()].into_iter().collect::<HashSet<_>>().len());
+
+        // Hashing and ordering do not access the dropped arena.
+        drop(arena2);
+        let set: HashSet<Thingy> = [x.clone(), y.clone()].into_iter().collect();
+        assert!(set.contains(&y));
+        assert_eq!(std::cmp::Ordering::Equal, y.cmp(&y.clone()));
+        assert_eq!(0, y.id());
+    }
+
+    #[test]
+    fn declaration_order_test() {
+        // The constructor evaluates the field initializers
+        // in the order the expansion lists them.
+        mod m {
+            use crate::smodel;
+            use std::cell::RefCell;
+
+            thread_local! {
+                pub static INITIALIZED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+            }
+
+            fn init(name: &'static str) -> i32 {
+                INITIALIZED.with(|d| d.borrow_mut().push(name));
+                0
+            }
+
+            smodel! {
+                mod smodel = crate;
+
+                type Arena = Arena;
+
+                pub struct Thingy {
+                    let z: i32 = init("z");
+                    let y: i32 = init("y");
+                    let x: i32 = init("x");
+                    let w: i32 = init("w");
+                    let v: i32 = init("v");
+                    let u: i32 = init("u");
+                    let t: i32 = init("t");
+                    let s: i32 = init("s");
+
+                    pub fn Thingy() {
+                        super();
+                    }
+                }
+            }
+        }
+
+        let arena = m::Arena::new();
+        m::Thingy::new(&arena);
+        assert_eq!(vec!["z", "y", "x", "w", "v", "u", "t", "s"], m::INITIALIZED.with(|d| d.borrow().clone()));
+    }
+
+    #[test]
+    fn arena_iteration_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+            }
+
+            struct FooBar: Foo {
+                pub fn FooBar() {
+                    super();
+                }
+            }
+
+            struct Qux: Thingy {
+                pub fn Qux() {
+                    super();
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        assert_eq!(true, arena.is_empty());
+
+        let foo = Foo::new(&arena);
+        let qux = Qux::new(&arena);
+        let foobar = FooBar::new(&arena);
+        let _ = Thingy::new(&arena);
+        assert_eq!(4, arena.len());
+
+        assert_eq!(vec![0, 1, 2, 3], arena.iter().map(|o: Thingy| o.id()).collect::<Vec<_>>());
+        assert_eq!(vec![foo.clone(), foobar.clone().into()], arena.iter_of::<Foo>().collect::<Vec<_>>());
+        assert_eq!(vec![foobar], arena.iter_of::<FooBar>().collect::<Vec<_>>());
+        assert_eq!(vec![qux], arena.iter_of::<Qux>().collect::<Vec<_>>());
+        assert_eq!(4, arena.iter_of::<Thingy>().count());
+    }
+
+    #[test]
+    fn dangling_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                let x: f64 = 0.0;
+                let ref name: String = "".into();
+
+                pub fn Thingy() {
+                    super();
+                }
+
+                pub fn checked_x(&self) -> Result<f64, crate::SModelError> {
+                    self.try_get_x()
+                }
+
+                pub fn set_checked_name(&self, name: &str) -> Result<(), crate::SModelError> {
+                    self.try_set_name(name.into())
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        let foo = Foo::new(&arena);
+        let thingy: Thingy = foo.clone().into();
+        assert!(thingy.is_alive());
+        assert_eq!(0.0, thingy.checked_x().unwrap());
+        assert!(thingy.set_checked_name("foo").is_ok());
+        assert!(thingy.to::<Foo>().is_ok());
+
+        drop(arena);
+        assert!(!thingy.is_alive());
+        assert!(matches!(thingy.checked_x(), Err(crate::SModelError::Dangling)));
+        assert!(matches!(thingy.set_checked_name("bar"), Err(crate::SModelError::Dangling)));
+        assert!(matches!(thingy.to::<Foo>(), Err(crate::SModelError::Dangling)));
+        assert!(!thingy.is::<Foo>());
+    }
+
+    #[test]
+    #[should_panic(expected = "Accessed field 'x' of 'Foo' after its arena was dropped.")]
+    fn verbose_panics_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            #[smodel(verbose_panics)]
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            struct Foo: Thingy {
+                let x: f64 = 0.0;
+
+                pub fn Foo() {
+                    super();
+                }
+
+                pub fn get_x(&self) -> f64 {
+                    self.x()
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        let foo = Foo::new(&arena);
+        drop(arena);
+        foo.get_x();
+    }
+
+    #[test]
+    fn error_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+            }
+
+            struct FooBar: Foo {
+                pub fn FooBar() {
+                    super();
+                }
+            }
+
+            struct Qux: Thingy {
+                pub fn Qux() {
+                    super();
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        let thingy: Thingy = Qux::new(&arena).into();
+        let err = thingy.to::<FooBar>().unwrap_err();
+        assert!(matches!(err, crate::SModelError::Contravariant { from: "Thingy", to: "FooBar", actual: "Qux" }));
+        assert_eq!("Cannot convert 'Thingy' to 'FooBar'; the object is a 'Qux'.", err.to_string());
+
+        let foo: Foo = Foo::new(&arena);
+        let err = foo.to::<FooBar>().unwrap_err();
+        assert!(matches!(err, crate::SModelError::Contravariant { from: "Foo", to: "FooBar", actual: "Foo" }));
+
+        drop(arena);
+        assert_eq!("The arena of the object was dropped.", foo.to::<FooBar>().unwrap_err().to_string());
+    }
+
+    #[test]
+    fn kind_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            abstract struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+            }
+
+            struct FooBar: Foo {
+                pub fn FooBar() {
+                    super();
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        let foobar = FooBar::new(&arena);
+        let thingy: Thingy = foobar.clone().into();
+        assert_eq!(ThingyTag::FooBar, thingy.kind());
+        assert_eq!("FooBar", thingy.type_name());
+        assert_eq!("Thingy", Thingy::static_type_name());
+        assert_eq!("FooBar", FooBar::static_type_name());
+        assert_eq!("Foo", ThingyTag::Foo.name());
+        assert_eq!(ThingyTag::Foo, Foo::new(&arena).kind());
+        assert_eq!("FooBar()", format!("{:?}", thingy));
+        assert_eq!("FooBar()", format!("{:?}", Foo::from(foobar)));
+    }
+
+    #[test]
+    fn debug_test() {
+        // Defined in a module so that field types may refer to data types.
+        mod model {
+            use crate::smodel;
+
+            smodel! {
+                mod smodel = crate;
+
+                type Arena = Arena;
+
+                #[smodel(debug)]
+                pub struct Thingy {
+                    let x: f64 = 0.0;
+
+                    pub fn Thingy() {
+                        super();
+                    }
+                }
+
+                pub struct Foo: Thingy {
+                    let ref name: String = "".into();
+                    let ref other: Option<Thingy> = None;
+
+                    pub fn Foo(name: &str) {
+                        super();
+                        self.set_name(name.into());
+                    }
+
+                    pub fn set_other_thingy(&self, other: Thingy) {
+                        self.set_other(Some(other));
+                    }
+                }
+
+                pub struct Qux: Thingy {
+                    pub fn Qux() {
+                        super();
+                    }
+                }
+            }
+        }
+        use model::*;
+
+        let arena = Arena::new();
+        let foo = Foo::new(&arena, "foo");
+        let thingy: Thingy = foo.clone().into();
+        assert_eq!(r#"Foo { x: 0.0, name: "foo", other: None }"#, format!("{:?}", thingy));
+        assert_eq!("Qux { x: 0.0 }", format!("{:?}", Qux::new(&arena)));
+
+        // Cycle
+        foo.set_other_thingy(thingy.clone());
+        assert_eq!(r#"Foo { x: 0.0, name: "foo", other: Some(Foo { .. }) }"#, format!("{:?}", foo));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        mod model {
+            use crate::{smodel, util::SharedArray};
+
+            smodel! {
+                mod smodel = crate;
+
+                type Arena = Arena;
+
+                #[smodel(serde)]
+                pub struct Thingy {
+                    let m_x: f64 = 0.0;
+
+                    pub fn Thingy() {
+                        super();
+                    }
+
+                    pub fn get_x(&self) -> f64 {
+                        self.m_x()
+                    }
+                }
+
+                pub struct Foo: Thingy {
+                    let ref m_name: String = "".into();
+                    let ref m_children: SharedArray<Thingy> = SharedArray::new();
+
+                    pub fn Foo(name: &str, x: f64) {
+                        super();
+                        self.set_m_name(name.into());
+                        self.set_m_x(x);
+                    }
+
+                    pub fn name(&self) -> String {
+                        self.m_name()
+                    }
+
+                    pub fn children(&self) -> SharedArray<Thingy> {
+                        self.m_children()
+                    }
+                }
+
+                pub struct Qux: Thingy {
+                    pub fn Qux() {
+                        super();
+                    }
+                }
+            }
+        }
+        use model::*;
+
+        let arena = Arena::new();
+        let foo = Foo::new(&arena, "foo", 10.0);
+        let qux = Qux::new(&arena);
+        foo.children().push(qux.into());
+        foo.children().push(foo.clone().into());
+
+        let json = serde_json::to_string(&arena).unwrap();
+        assert_eq!(r#"{"types":["Foo","Qux"],"fields":[[10.0,"foo",[1,0]],[0.0]]}"#, json);
+
+        let arena: Arena = serde_json::from_str(&json).unwrap();
+        assert_eq!(2, arena.len());
+        let foo = arena.get_by_id(0).unwrap().to::<Foo>().unwrap();
+        assert_eq!("foo", foo.name());
+        assert_eq!(10.0, foo.get_x());
+        assert!(foo.children().get(0).unwrap().is::<Qux>());
+        assert_eq!(foo.clone(), foo.children().get(1).unwrap().to::<Foo>().unwrap());
+
+        // A reference to an object of another arena is an error.
+        let arena2 = Arena::new();
+        let foo2 = Foo::new(&arena2, "foo2", 0.0);
+        foo2.children().push(Qux::new(&arena).into());
+        let error = serde_json::to_string(&arena2).unwrap_err();
+        assert_eq!("object serialized outside of its arena", error.to_string());
+    }
+
+    #[test]
+    fn deep_clone_test() {
+        mod model {
+            use crate::{smodel, util::SharedArray};
+
+            smodel! {
+                mod smodel = crate;
+
+                type Arena = Arena;
+
+                pub struct Thingy {
+                    let m_x: f64 = 0.0;
+
+                    pub fn Thingy() {
+                        super();
+                    }
+
+                    pub fn x(&self) -> f64 {
+                        self.m_x()
+                    }
+
+                    pub fn set_x(&self, x: f64) {
+                        self.set_m_x(x);
+                    }
+                }
+
+                pub struct Foo: Thingy {
+                    let ref m_parent: Option<Thingy> = None;
+                    let ref m_children: SharedArray<Thingy> = SharedArray::new();
+
+                    pub fn Foo() {
+                        super();
+                    }
+
+                    pub fn parent(&self) -> Option<Thingy> {
+                        self.m_parent()
+                    }
+
+                    pub fn children(&self) -> SharedArray<Thingy> {
+                        self.m_children()
+                    }
+
+                    pub fn add_child(&self, child: Thingy) {
+                        if let Ok(foo) = child.to::<Foo>() {
+                            foo.set_m_parent(Some(self.clone().into()));
+                        }
+                        self.m_children().push(child);
+                    }
+                }
+
+                pub struct Qux: Thingy {
+                    pub fn Qux() {
+                        super();
+                    }
+                }
+            }
+        }
+        use model::*;
+
+        let arena = Arena::new();
+        let foo = Foo::new(&arena);
+        let child = Foo::new(&arena);
+        let qux = Qux::new(&arena);
+        qux.set_x(10.0);
+        foo.add_child(child.clone().into());
+        foo.add_child(qux.into());
+
+        let arena2 = Arena::new();
+        let _ = Qux::new(&arena2);
+        let thingy: Thingy = foo.clone().into();
+        let foo2 = thingy.deep_clone_into(&arena2).to::<Foo>().unwrap();
+        assert_eq!(4, arena2.len());
+        assert_eq!(1, foo2.id());
+
+        let child2 = foo2.children().get(0).unwrap().to::<Foo>().unwrap();
+        assert_ne!(child, child2);
+        assert_eq!(Some(foo2.clone().into()), child2.parent());
+        assert_eq!("Qux", foo2.children().get(1).unwrap().type_name());
+        assert_eq!(10.0, foo2.children().get(1).unwrap().x());
+
+        // The copy is independent of the original.
+        foo2.children().get(1).unwrap().set_x(5.0);
+        assert_eq!(10.0, foo.children().get(1).unwrap().x());
+        assert_eq!(2, foo.children().length());
+    }
+
+    #[test]
+    fn deep_clone_container_test() {
+        mod model {
+            use crate::{smodel, util::{SharedArray, SharedMap}};
+
+            smodel! {
+                mod smodel = crate;
+
+                type Arena = Arena;
+
+                pub struct Thingy {
+                    let ref m_names: SharedArray<String> = SharedArray::new();
+                    let ref m_counts: SharedMap<String, usize> = SharedMap::new();
+
+                    pub fn Thingy() {
+                        super();
+                    }
+
+                    pub fn names(&self) -> SharedArray<String> {
+                        self.m_names()
+                    }
+
+                    pub fn counts(&self) -> SharedMap<String, usize> {
+                        self.m_counts()
+                    }
+                }
+            }
+        }
+        use model::*;
+
+        let arena = Arena::new();
+        let x = Thingy::new(&arena);
+        x.names().push("a".into());
+        x.counts().set("a".into(), 1);
+
+        let arena2 = Arena::new();
+        let x2 = x.deep_clone_into(&arena2);
+        assert_eq!(vec!["a".to_owned()], x2.names().iter().collect::<Vec<_>>());
+        assert_eq!(Some(1), x2.counts().get(&"a".to_owned()));
+
+        // The containers of the copy are new containers.
+        x2.names().push("b".into());
+        x2.counts().set("b".into(), 2);
+        assert_eq!(1, x.names().length());
+        assert_eq!(2, x2.names().length());
+        assert_eq!(None, x.counts().get(&"b".to_owned()));
+    }
+
+    #[test]
+    fn generics_test() {
+        mod model {
+            use crate::smodel;
+
+            smodel! {
+                mod smodel = crate;
+
+                type Arena = Arena;
+
+                #[smodel(debug)]
+                pub struct Thingy<T: Clone + 'static> {
+                    let ref m_value: Option<T> = None;
+
+                    pub fn Thingy(value: T) {
+                        super();
+                        self.set_m_value(Some(value));
+                    }
+
+                    pub fn value(&self) -> Option<T> {
+                        self.m_value()
+                    }
+
+                    pub fn describe(&self) -> String {
+                        "Thingy".into()
+                    }
+                }
+
+                pub struct Foo<T>: Thingy<T> {
+                    let ref m_other: Option<Thingy<T>> = None;
+
+                    pub fn Foo(_value: T) {
+                        super(_value.clone());
+                    }
+
+                    pub fn other(&self) -> Option<Thingy<T>> {
+                        self.m_other()
+                    }
+
+                    pub fn set_other(&self, other: Thingy<T>) {
+                        self.set_m_other(Some(other));
+                    }
+
+                    pub override fn describe(&self) -> String {
+                        format!("Foo({})", super.describe())
+                    }
+                }
+            }
+        }
+        use model::*;
+
+        let arena: Arena<String> = Arena::new();
+        let thingy = Thingy::new(&arena, "a".to_owned());
+        let foo = Foo::new(&arena, "b".to_owned());
+        foo.set_other(thingy.clone());
+        let base: Thingy<String> = foo.clone().into();
+        assert_eq!(Some("b".to_owned()), base.value());
+        assert_eq!("Foo(Thingy)", base.describe());
+        assert_eq!(Some(thingy.clone()), base.to::<Foo<String>>().unwrap().other());
+        assert!(thingy.to::<Foo<String>>().is_err());
+        assert_eq!(r#"Foo { m_value: Some("b"), m_other: Some(Thingy { m_value: Some("a") }) }"#, format!("{base:?}"));
+
+        let arena2: Arena<String> = Arena::new();
+        let foo2 = foo.deep_clone_into(&arena2);
+        assert_eq!(Some("b".to_owned()), foo2.value());
+        assert_eq!(Some("a".to_owned()), foo2.other().unwrap().value());
+
+        let numbers: Arena<i32> = Arena::new();
+        assert_eq!(Some(10), Foo::new(&numbers, 10).value());
+    }
+
+    #[test]
+    fn trait_impl_test() {
+        use crate::smodel;
+        use std::fmt::Display;
+
+        pub trait Describe {
+            const KIND: &'static str;
+            fn describe(&self) -> String;
+            fn kind_name() -> &'static str;
+        }
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                let m_x: f64 = 0.0;
+
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            impl Display for Thingy {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "Thingy({})", self.m_x())
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+            }
+
+            impl Display for Foo {
+                override fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "Foo of ")?;
+                    super.fmt(f)
+                }
+            }
+
+            struct Bar: Foo {
+                pub fn Bar() {
+                    super();
+                }
+            }
+
+            impl Describe for Foo {
+                const KIND: &'static str = "Foo";
+
+                fn describe(&self) -> String {
+                    format!("{} {}", Self::KIND, self.m_x())
+                }
+
+                fn kind_name() -> &'static str {
+                    "Foo"
+                }
+            }
+
+            impl Describe for Bar {
+                override fn describe(&self) -> String {
+                    format!("Bar; {}", super.describe())
+                }
+
+                fn kind_name() -> &'static str {
+                    "Bar"
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        let thingy = Thingy::new(&arena);
+        assert_eq!("Thingy(0)", thingy.to_string());
+        let foo = Foo::new(&arena);
+        foo.set_m_x(1.0);
+        assert_eq!("Foo of Thingy(1)", foo.to_string());
+        assert_eq!("Foo of Thingy(1)", Thingy::from(foo.clone()).to_string());
+
+        // `Bar` inherits the `Display` override through its base.
+        let bar = Bar::new(&arena);
+        assert_eq!("Foo of Thingy(0)", Thingy::from(bar.clone()).to_string());
+
+        // Dispatch through `Foo` invokes the `Bar` override.
+        assert_eq!("Foo 1", foo.describe());
+        assert_eq!("Bar; Foo 0", Foo::from(bar.clone()).describe());
+        assert_eq!("Bar; Foo 0", bar.describe());
+        assert_eq!("Foo", <Bar as Describe>::KIND);
+        assert_eq!("Bar", Bar::kind_name());
+    }
+
+    #[test]
+    fn trait_path_test() {
+        use crate::smodel;
+
+        mod a {
+            pub trait Describe {
+                fn describe(&self) -> String;
+            }
+        }
+
+        mod b {
+            pub trait Describe {
+                fn describe(&self) -> String;
+            }
+
+            pub trait Debug {
+                fn debug(&self) -> String;
+            }
+        }
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            impl a::Describe for Thingy {
+                fn describe(&self) -> String {
+                    "a".into()
+                }
+            }
+
+            impl b::Describe for Thingy {
+                fn describe(&self) -> String {
+                    "b".into()
+                }
+            }
+
+            impl b::Debug for Thingy {
+                fn debug(&self) -> String {
+                    "Thingy".into()
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+            }
+
+            impl a::Describe for Foo {
+                override fn describe(&self) -> String {
+                    format!("Foo; {}", super.describe())
+                }
+            }
+
+            impl b::Describe for Foo {}
+        }
+
+        // Traits of the same name are told apart by their paths.
+        let arena = Arena::new();
+        let foo = Thingy::from(Foo::new(&arena));
+        assert_eq!("Foo; a", a::Describe::describe(&foo));
+        assert_eq!("b", b::Describe::describe(&foo));
+
+        // A trait named `Debug` other than `std::fmt::Debug` keeps
+        // the generated `Debug` implementation.
+        assert_eq!("Thingy", b::Debug::debug(&foo));
+        assert_eq!("Foo()", format!("{:?}", foo));
+    }
+
+    #[test]
+    fn receiver_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                let m_x: f64 = 0.0;
+
+                pub fn Thingy() {
+                    super();
+                }
+
+                pub fn x(&self) -> f64 {
+                    self.m_x()
+                }
+
+                pub fn with_x(self, x: f64) -> Self {
+                    self.set_m_x(x);
+                    self
+                }
+
+                pub fn describe(self) -> String {
+                    format!("Thingy {}", self.m_x())
+                }
+
+                pub fn or_self(&self, other: Option<Self>) -> Option<Self> {
+                    other
+                }
+            }
+
+            struct Foo: Thingy {
+                let m_y: f64 = 0.0;
+
+                pub fn Foo() {
+                    super();
+                }
+
+                pub fn y(&self) -> f64 {
+                    self.m_y()
+                }
+
+                pub final fn with_y(mut self, y: f64) -> Self {
+                    self.set_m_y(y);
+                    self = self.clone();
+                    self
+                }
+
+                pub override fn with_x(self, x: f64) -> Self {
+                    super.with_x(x * 2.0);
+                    self
+                }
+
+                pub override fn describe(self) -> String {
+                    format!("Foo; {}", super.describe())
+                }
+
+                // `Self` within a type stands for the declaring data type.
+                pub override fn or_self(&self, other: Option<Thingy>) -> Option<Thingy> {
+                    other.or(Some(self.clone().into()))
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        let foo = Foo::new(&arena).with_x(1.0).with_y(3.0);
+        assert_eq!(2.0, foo.x());
+        assert_eq!(3.0, foo.y());
+
+        // The override is dispatched and its result converted into `Thingy`.
+        let thingy: Thingy = Thingy::from(foo.clone()).with_x(2.0);
+        assert_eq!(4.0, thingy.x());
+        assert!(thingy.is::<Foo>());
+        assert_eq!("Foo; Thingy 4", thingy.describe());
+        assert_eq!("Thingy 5", Thingy::new(&arena).with_x(5.0).describe());
+        assert_eq!(Some(Thingy::from(foo.clone())), Thingy::from(foo.clone()).or_self(None));
+        assert_eq!(None, Thingy::new(&arena).or_self(None));
+    }
+
+    #[test]
+    fn parameters_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                let m_x: f64 = 0.0;
+
+                pub fn Thingy((x, _): (f64, f64)) {
+                    super();
+                    self.set_m_x(x);
+                }
+
+                pub fn area(&self, (w, h): (f64, f64), _: bool) -> f64 {
+                    w * h
+                }
+
+                pub fn scale(&self, mut factor: f64) -> f64 {
+                    factor *= self.m_x();
+                    factor
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo(_x: f64) {
+                    super((_x, 0.0));
+                }
+
+                pub override fn area(&self, size: (f64, f64), doubled: bool) -> f64 {
+                    let area = super.area(size, doubled);
+                    if doubled { area * 2.0 } else { area }
+                }
+
+                pub override fn scale(&self, f: f64) -> f64 {
+                    super.scale(f) + 1.0
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        let thingy = Thingy::new(&arena, (2.0, 5.0));
+        assert_eq!(6.0, thingy.area((2.0, 3.0), true));
+        assert_eq!(4.0, thingy.scale(2.0));
+
+        let foo: Thingy = Foo::new(&arena, 3.0).into();
+        assert_eq!(12.0, foo.area((2.0, 3.0), true));
+        assert_eq!(6.0, foo.area((2.0, 3.0), false));
+        assert_eq!(7.0, foo.scale(2.0));
+    }
+
+    #[test]
+    fn override_signature_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+
+                pub fn repeat<T: ToString>(&self, value: T, count: usize) -> String {
+                    value.to_string().repeat(count)
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+
+                // Parameter names may differ from the overriden method.
+                pub override fn repeat<T: ToString>(&self, v: T, n: usize) -> String {
+                    format!("[{}]", super.repeat(v, n))
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        assert_eq!("abab", Thingy::new(&arena).repeat("ab", 2));
+        let foo: Thingy = Foo::new(&arena).into();
+        assert_eq!("[111]", foo.repeat(1, 3));
+    }
+
+    #[test]
+    fn hiding_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+
+                pub fn name(&self) -> String {
+                    "Thingy".into()
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+
+                pub new fn name(&self) -> String {
+                    "Foo".into()
+                }
+            }
+
+            struct Bar: Foo {
+                pub fn Bar() {
+                    super();
+                }
+
+                pub override fn name(&self) -> String {
+                    format!("Bar of {}", super.name())
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        let foo = Foo::new(&arena);
+        assert_eq!("Foo", foo.name());
+
+        // Dispatch from `Thingy` ignores the hiding method.
+        assert_eq!("Thingy", Thingy::from(foo).name());
+
+        // `Bar` overrides the hiding method.
+        let bar = Bar::new(&arena);
+        assert_eq!("Bar of Foo", Foo::from(bar.clone()).name());
+        assert_eq!("Thingy", Thingy::from(bar).name());
+    }
+
+    #[test]
+    fn interface_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            interface Callable {
+                fn call(&self, x: i32) -> String;
+            }
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            struct Foo: Thingy, Callable {
+                pub fn Foo() {
+                    super();
+                }
+
+                pub fn call(&self, x: i32) -> String {
+                    format!("Foo {x}")
+                }
+            }
+
+            struct FooSub: Foo {
+                pub fn FooSub() {
+                    super();
+                }
+
+                pub override fn call(&self, x: i32) -> String {
+                    format!("FooSub {x}")
+                }
+            }
+
+            struct Bar: Thingy {
+                pub fn Bar() {
+                    super();
+                }
+            }
+
+            struct Qux: Bar, Callable {
+                pub fn Qux() {
+                    super();
+                }
+
+                pub fn call(&self, x: i32) -> String {
+                    format!("Qux {x}")
+                }
+            }
+        }
+
+        let arena = Arena::new();
+        let foo: Thingy = Foo::new(&arena).into();
+        let foo_sub: Thingy = FooSub::new(&arena).into();
+        let bar: Thingy = Bar::new(&arena).into();
+        let qux: Thingy = Qux::new(&arena).into();
+
+        assert!(foo.is_interface::<Callable>());
+        assert!(qux.is_interface::<Callable>());
+        assert!(!bar.is_interface::<Callable>());
+        assert!(bar.as_callable().is_none());
+
+        assert_eq!("Foo 1", foo.as_callable().unwrap().call(1));
+        assert_eq!("FooSub 2", foo_sub.as_callable().unwrap().call(2));
+        assert_eq!("Qux 3", qux.as_callable().unwrap().call(3));
+
+        // Converting to and from an interface.
+        let callable = Callable::from(Qux::new(&arena));
+        assert_eq!("Qux 4", callable.call(4));
+        assert!(Thingy::from(callable).is::<Qux>());
+    }
+
+    #[test]
+    fn visitor_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            struct FooBar: Thingy {
+                pub fn FooBar() {
+                    super();
+                }
+            }
+
+            struct Qux: FooBar {
+                pub fn Qux() {
+                    super();
+                }
+            }
+
+            struct Bar: Thingy {
+                pub fn Bar() {
+                    super();
+                }
+            }
+        }
+
+        struct Names(Vec<String>);
+
+        impl ThingyVisitor for Names {
+            fn visit_thingy(&mut self, _node: &Thingy) {
+                self.0.push("Thingy".into());
+            }
+
+            fn visit_foo_bar(&mut self, _node: &FooBar) {
+                self.0.push("FooBar".into());
+            }
+        }
+
+        let arena = Arena::new();
+        let mut names = Names(vec![]);
+        Thingy::new(&arena).accept(&mut names);
+        FooBar::new(&arena).accept(&mut names);
+
+        // `visit_qux` defaults to `visit_foo_bar`, and
+        // `visit_bar` defaults to `visit_thingy`.
+        Qux::new(&arena).accept(&mut names);
+        Bar::new(&arena).accept(&mut names);
+
+        assert_eq!(vec!["Thingy", "FooBar", "FooBar", "Thingy"], names.0);
+    }
+
+    #[test]
+    fn as_kind_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            abstract struct Thingy {
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            struct Foo: Thingy {
+                pub fn Foo() {
+                    super();
+                }
+            }
+
+            struct FooBar: Foo {
+                pub fn FooBar() {
+                    super();
+                }
+            }
+        }
+
+        fn describe(thingy: &Thingy) -> String {
+            match thingy.as_kind() {
+                ThingyKind::Foo(foo) => format!("Foo {}", foo.id()),
+                ThingyKind::FooBar(foobar) => format!("FooBar {}", foobar.id()),
+            }
+        }
+
+        let arena = Arena::new();
+        let foo: Thingy = Foo::new(&arena).into();
+        let foobar = FooBar::new(&arena);
+        assert_eq!("Foo 0", describe(&foo));
+        assert_eq!("FooBar 1", describe(&foobar.clone().into()));
+        assert_eq!(ThingyKind::FooBar(foobar.clone()), Thingy::from(foobar).as_kind());
+    }
+
+    #[test]
+    fn const_test() {
+        use crate::smodel;
+
+        smodel! {
+            mod smodel = crate;
+
+            type Arena = Arena;
+
+            struct Thingy {
+                pub const KIND_NAME: &'static str = "Thingy";
+                pub static SIZES: [u32; 2] = [0, 0];
+
+                pub fn Thingy() {
+                    super();
+                }
+            }
+
+            struct FooBar: Thingy {
+                pub override const KIND_NAME: &'static str = "FooBar";
+                pub override static SIZES: [u32; 2] = [1, 2];
+
+                pub fn FooBar() {
+                    super();
+                }
+            }
+
+            struct Qux: FooBar {
+                pub fn Qux() {
+                    super();
+                }
+            }
+        }
+
+        assert_eq!("Thingy", Thingy::KIND_NAME);
+        assert_eq!("FooBar", FooBar::KIND_NAME);
+        assert_eq!("FooBar", Qux::KIND_NAME);
+        assert_eq!([1, 2], *Qux::SIZES);
+        assert!(std::ptr::eq(FooBar::SIZES, Qux::SIZES));
+
+        let arena = Arena::new();
+        let thingy = Thingy::new(&arena);
+        let qux: Thingy = Qux::new(&arena).into();
+        assert_eq!("Thingy", thingy.kind_name_const());
+        assert_eq!("FooBar", qux.kind_name_const());
+        assert_eq!([0, 0], *thingy.sizes_static());
+        assert_eq!([1, 2], *qux.sizes_static());
+    }
+
+    #[test]
+    fn field_visibility_test() {
+        // Defined in a module so that accessors are used
+        // outside of the enclosing module.
+        mod model {
+            use crate::smodel;
+
+            smodel! {
+                mod smodel = crate;
+
+                type Arena = Arena;
+
+                pub struct Thingy {
+                    /// The name of the thingy.
+                    pub let ref name: String = "".into();
+                    pub(crate) let x: i32 = 0;
+                    pub(get) let count: usize = 0;
+
+                    pub fn Thingy() {
+                        super();
+                    }
+
+                    pub fn increment(&self) {
+                        self.set_count(self.count() + 1);
+                    }
+                }
+            }
+        }
+
+        use model::*;
+
+        let arena = Arena::new();
+        let thingy = Thingy::new(&arena);
+        thingy.set_name("Thingy".into());
+        thingy.set_x(10);
+        thingy.increment();
+        thingy.increment();
+        assert_eq!("Thingy", thingy.name());
+        assert_eq!(10, thingy.try_get_x().unwrap());
+        assert_eq!(2, thingy.count());
+    }
+}
@@ -0,0 +1,221 @@
+//! Serialization of arenas, available with the `serde` feature.
+
+use std::{any::Any, cell::RefCell, fmt, marker::PhantomData, ptr, rc::{Rc, Weak}};
+use serde::{de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor}, ser::{SerializeSeq, SerializeStruct}, Deserialize, Deserializer, Serialize, Serializer};
+use crate::Arena;
+
+/// Implemented by the basemost data structure of a model
+/// with the `#[smodel(serde)]` option.
+#[doc(hidden)]
+pub trait ArenaSerde: Sized + 'static {
+    /// Returns the name of the data type an object was constructed as.
+    fn type_name(data: &Rc<Self>) -> &'static str;
+
+    /// Allocates an object of the given data type with default
+    /// field values, without invoking its constructor. Returns
+    /// `false` if there is no such data type.
+    fn allocate_default(arena: &Arena<Self>, type_name: &str) -> bool;
+
+    /// Serializes the fields of an object as a tuple.
+    fn serialize_fields<S: Serializer>(data: &Rc<Self>, serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Deserializes the fields of an object from a tuple.
+    fn deserialize_fields<'de, D: Deserializer<'de>>(data: &Rc<Self>, deserializer: D) -> Result<(), D::Error>;
+}
+
+thread_local! {
+    /// The objects of the arena being deserialized, as a `Vec<Rc<T>>`.
+    static OBJECTS: RefCell<Option<Rc<dyn Any>>> = const { RefCell::new(None) };
+
+    /// The objects of the arena being serialized, as a `Vec<Rc<T>>`.
+    static SERIALIZED_OBJECTS: RefCell<Option<Rc<dyn Any>>> = const { RefCell::new(None) };
+}
+
+/// Serializes a reference to an object as its object ID, failing
+/// if the object does not belong to the arena being serialized.
+#[doc(hidden)]
+pub fn serialize_id<T: 'static, S: Serializer>(data: &Weak<T>, id: usize, serializer: S) -> Result<S::Ok, S::Error> {
+    if data.strong_count() == 0 {
+        return Err(serde::ser::Error::custom("object serialized after its arena was dropped"));
+    }
+    let belongs = SERIALIZED_OBJECTS.with(|objects| {
+        let objects = objects.borrow();
+        objects.as_ref().and_then(|o| o.downcast_ref::<Vec<Rc<T>>>())
+            .and_then(|o| o.get(id))
+            .is_some_and(|o| ptr::eq(Rc::as_ptr(o), data.as_ptr()))
+    });
+    if !belongs {
+        return Err(serde::ser::Error::custom("object serialized outside of its arena"));
+    }
+    id.serialize(serializer)
+}
+
+/// Resolves an object ID read while deserializing an arena.
+#[doc(hidden)]
+pub fn resolve_id<T: 'static, E: de::Error>(id: usize) -> Result<Rc<T>, E> {
+    OBJECTS.with(|objects| {
+        let objects = objects.borrow();
+        let Some(objects) = objects.as_ref().and_then(|o| o.downcast_ref::<Vec<Rc<T>>>()) else {
+            return Err(E::custom("object deserialized outside of its arena"));
+        };
+        objects.get(id).cloned().ok_or_else(|| E::custom(format!("invalid object ID {id}")))
+    })
+}
+
+/// Serializes an arena as the data type names of its objects followed
+/// by their fields. References to objects are serialized as object IDs.
+impl<T: ArenaSerde> Serialize for Arena<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let objects: Vec<Rc<T>> = self.data.borrow().clone();
+        let types: Vec<&'static str> = objects.iter().map(T::type_name).collect();
+
+        // Expose the objects for checking serialized references,
+        // restoring the previous objects afterwards.
+        struct Restore(Option<Rc<dyn Any>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SERIALIZED_OBJECTS.with(|objects| *objects.borrow_mut() = self.0.take());
+            }
+        }
+        let serialized: Rc<dyn Any> = Rc::new(objects.clone());
+        let _restore = Restore(SERIALIZED_OBJECTS.with(|o| o.borrow_mut().replace(serialized)));
+
+        let mut s = serializer.serialize_struct("Arena", 2)?;
+        s.serialize_field("types", &types)?;
+        s.serialize_field("fields", &Fields(&objects))?;
+        s.end()
+    }
+}
+
+struct Fields<'a, T>(&'a [Rc<T>]);
+
+impl<T: ArenaSerde> Serialize for Fields<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_seq(Some(self.0.len()))?;
+        for obj in self.0 {
+            s.serialize_element(&ObjectFields(obj))?;
+        }
+        s.end()
+    }
+}
+
+struct ObjectFields<'a, T>(&'a Rc<T>);
+
+impl<T: ArenaSerde> Serialize for ObjectFields<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::serialize_fields(self.0, serializer)
+    }
+}
+
+/// Deserializes an arena in two phases: first every object is allocated
+/// with default field values, then fields are assigned, allowing for
+/// circular references. Constructors are not invoked.
+impl<'de, T: ArenaSerde> Deserialize<'de> for Arena<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Arena", &["types", "fields"], ArenaVisitor(PhantomData))
+    }
+}
+
+struct ArenaVisitor<T>(PhantomData<T>);
+
+impl<'de, T: ArenaSerde> Visitor<'de> for ArenaVisitor<T> {
+    type Value = Arena<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an arena")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let arena = Arena::new();
+        let types: Vec<String> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let objects = allocate(&arena, &types)?;
+        seq.next_element_seed(FieldsSeed(&objects))?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(arena)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let arena = Arena::new();
+        let mut objects: Option<Vec<Rc<T>>> = None;
+        let mut has_fields = false;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "types" => {
+                    let types: Vec<String> = map.next_value()?;
+                    objects = Some(allocate(&arena, &types)?);
+                },
+                "fields" => {
+                    let Some(objects) = objects.as_ref() else {
+                        return Err(de::Error::custom("`types` must precede `fields`"));
+                    };
+                    map.next_value_seed(FieldsSeed(objects))?;
+                    has_fields = true;
+                },
+                _ => {
+                    map.next_value::<de::IgnoredAny>()?;
+                },
+            }
+        }
+        if objects.is_none() {
+            return Err(de::Error::missing_field("types"));
+        }
+        if !has_fields {
+            return Err(de::Error::missing_field("fields"));
+        }
+        Ok(arena)
+    }
+}
+
+fn allocate<T: ArenaSerde, E: de::Error>(arena: &Arena<T>, types: &[String]) -> Result<Vec<Rc<T>>, E> {
+    for name in types {
+        if !T::allocate_default(arena, name) {
+            return Err(E::custom(format!("unknown data type '{name}'")));
+        }
+    }
+    Ok(arena.data.borrow().clone())
+}
+
+struct FieldsSeed<'a, T>(&'a [Rc<T>]);
+
+impl<'de, T: ArenaSerde> DeserializeSeed<'de> for FieldsSeed<'_, T> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        // Expose the objects for resolving object IDs,
+        // restoring the previous objects afterwards.
+        struct Restore(Option<Rc<dyn Any>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                OBJECTS.with(|objects| *objects.borrow_mut() = self.0.take());
+            }
+        }
+        let objects: Rc<dyn Any> = Rc::new(self.0.to_vec());
+        let _restore = Restore(OBJECTS.with(|o| o.borrow_mut().replace(objects)));
+
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: ArenaSerde> Visitor<'de> for FieldsSeed<'_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the fields of {} objects", self.0.len())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        for (i, obj) in self.0.iter().enumerate() {
+            seq.next_element_seed(ObjectFieldsSeed(obj))?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        Ok(())
+    }
+}
+
+struct ObjectFieldsSeed<'a, T>(&'a Rc<T>);
+
+impl<'de, T: ArenaSerde> DeserializeSeed<'de> for ObjectFieldsSeed<'_, T> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        T::deserialize_fields(self.0, deserializer)
+    }
+}
@@ -142,6 +142,46 @@ impl<A> Extend<A> for SharedArray<A> {
     }
 }
 
+/// Copies the content of the array into a new array, preserving
+/// arrays shared within the deep clone.
+impl<T: Clone + 'static> crate::DeepCloneContainer for SharedArray<T> {
+    type Element = T;
+    fn deep_clone_with(&self, cx: &mut crate::DeepCloneContext, mut element: impl FnMut(&T, &mut crate::DeepCloneContext) -> T) -> Self {
+        let source = Rc::as_ptr(&self.0) as *const ();
+        if let Some(copy) = cx.get_copy::<Self>(source) {
+            return copy;
+        }
+        let mut copy = Self::new();
+        cx.set_copy(source, copy.clone());
+        for v in self.0.borrow().iter() {
+            copy.push(element(v, cx));
+        }
+        copy
+    }
+}
+
+impl<T: crate::DeepClone + Clone + 'static> crate::DeepClone for SharedArray<T> {
+    fn deep_clone(&self, cx: &mut crate::DeepCloneContext) -> Self {
+        crate::DeepCloneContainer::deep_clone_with(self, cx, |v, cx| v.deep_clone(cx))
+    }
+}
+
+/// Serializes the content of the array.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for SharedArray<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.borrow().serialize(serializer)
+    }
+}
+
+/// Deserializes the content of the array into a new array.
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for SharedArray<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self(Rc::new(RefCell::new(Vec::deserialize(deserializer)?))))
+    }
+}
+
 #[macro_export]
 macro_rules! shared_array {
     ($($element:expr),*) => {
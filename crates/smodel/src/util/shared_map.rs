@@ -139,6 +139,45 @@ impl<K, V> Extend<(K, V)> for SharedMap<K, V> where K: Eq + Hash {
     }
 }
 
+/// Copies the content of the map into a new map, preserving
+/// maps shared within the deep clone.
+impl<K: Clone + Eq + Hash + 'static, V: Clone + 'static> crate::DeepCloneContainer for SharedMap<K, V> {
+    type Element = V;
+    fn deep_clone_with(&self, cx: &mut crate::DeepCloneContext, mut element: impl FnMut(&V, &mut crate::DeepCloneContext) -> V) -> Self {
+        let source = Rc::as_ptr(&self.0) as *const ();
+        if let Some(copy) = cx.get_copy::<Self>(source) {
+            return copy;
+        }
+        let mut copy = Self::new();
+        cx.set_copy(source, copy.clone());
+        let entries: Vec<(K, V)> = self.borrow().iter().map(|(k, v)| (k.clone(), element(v, cx))).collect();
+        copy.extend(entries);
+        copy
+    }
+}
+
+impl<K: Clone + Eq + Hash + 'static, V: crate::DeepClone + Clone + 'static> crate::DeepClone for SharedMap<K, V> {
+    fn deep_clone(&self, cx: &mut crate::DeepCloneContext) -> Self {
+        crate::DeepCloneContainer::deep_clone_with(self, cx, |v, cx| v.deep_clone(cx))
+    }
+}
+
+/// Serializes the content of the map.
+#[cfg(feature = "serde")]
+impl<K: serde::Serialize, V: serde::Serialize> serde::Serialize for SharedMap<K, V> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.borrow().serialize(serializer)
+    }
+}
+
+/// Deserializes the content of the map into a new map.
+#[cfg(feature = "serde")]
+impl<'de, K: serde::Deserialize<'de> + Eq + Hash, V: serde::Deserialize<'de>> serde::Deserialize<'de> for SharedMap<K, V> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self(Rc::new(RefCell::new(HashMap::deserialize(deserializer)?))))
+    }
+}
+
 #[macro_export]
 macro_rules! shared_map {
     ($($key:expr => $value:expr),*) => {
@@ -0,0 +1,16 @@
+use smodel::smodel;
+
+smodel! {
+    type Arena = Arena;
+
+    abstract struct Thingy {
+        pub fn Thingy() {
+            super();
+        }
+    }
+}
+
+fn main() {
+    let arena = Arena::new();
+    Thingy::new(&arena);
+}
@@ -0,0 +1,21 @@
+use smodel::smodel;
+
+smodel! {
+    type Arena = Arena;
+
+    abstract struct Thingy {
+        pub fn Thingy() {
+            super();
+        }
+
+        pub abstract fn name(&self) -> String;
+    }
+
+    struct Foo: Thingy {
+        pub fn Foo() {
+            super();
+        }
+    }
+}
+
+fn main() {}
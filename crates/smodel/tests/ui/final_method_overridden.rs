@@ -0,0 +1,27 @@
+use smodel::smodel;
+
+smodel! {
+    type Arena = Arena;
+
+    struct Thingy {
+        pub fn Thingy() {
+            super();
+        }
+
+        pub final fn name(&self) -> String {
+            "Thingy".into()
+        }
+    }
+
+    struct Foo: Thingy {
+        pub fn Foo() {
+            super();
+        }
+
+        pub override fn name(&self) -> String {
+            "Foo".into()
+        }
+    }
+}
+
+fn main() {}
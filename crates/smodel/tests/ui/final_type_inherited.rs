@@ -0,0 +1,25 @@
+use smodel::smodel;
+
+smodel! {
+    type Arena = Arena;
+
+    struct Thingy {
+        pub fn Thingy() {
+            super();
+        }
+    }
+
+    final struct Foo: Thingy {
+        pub fn Foo() {
+            super();
+        }
+    }
+
+    struct Bar: Foo {
+        pub fn Bar() {
+            super();
+        }
+    }
+}
+
+fn main() {}
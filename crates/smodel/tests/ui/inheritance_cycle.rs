@@ -0,0 +1,19 @@
+use smodel::smodel;
+
+smodel! {
+    type Arena = Arena;
+
+    struct A: B {
+        pub fn A() {
+            super();
+        }
+    }
+
+    struct B: A {
+        pub fn B() {
+            super();
+        }
+    }
+}
+
+fn main() {}
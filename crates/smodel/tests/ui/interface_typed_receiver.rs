@@ -0,0 +1,17 @@
+use smodel::smodel;
+
+smodel! {
+    type Arena = Arena;
+
+    interface Callable {
+        fn call(self: Box<Self>, x: i32) -> String;
+    }
+
+    struct Thingy {
+        pub fn Thingy() {
+            super();
+        }
+    }
+}
+
+fn main() {}
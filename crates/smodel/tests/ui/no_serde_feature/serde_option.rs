@@ -0,0 +1,16 @@
+use smodel::smodel;
+
+smodel! {
+    type Arena = Arena;
+
+    #[smodel(serde)]
+    struct Thingy {
+        let x: f64 = 0.0;
+
+        pub fn Thingy() {
+            super();
+        }
+    }
+}
+
+fn main() {}
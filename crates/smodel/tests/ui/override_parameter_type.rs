@@ -0,0 +1,23 @@
+use smodel::smodel;
+
+smodel! {
+    type Arena = Arena;
+
+    struct Thingy {
+        pub fn Thingy() {
+            super();
+        }
+
+        pub fn m(&self, _a: u32) {}
+    }
+
+    struct Foo: Thingy {
+        pub fn Foo() {
+            super();
+        }
+
+        pub override fn m(&self, _a: u64) {}
+    }
+}
+
+fn main() {}
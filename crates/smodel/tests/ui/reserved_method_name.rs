@@ -0,0 +1,17 @@
+use smodel::smodel;
+
+smodel! {
+    type Arena = Arena;
+
+    struct Thingy {
+        pub fn Thingy() {
+            super();
+        }
+
+        pub fn kind(&self) -> String {
+            "thingy".into()
+        }
+    }
+}
+
+fn main() {}